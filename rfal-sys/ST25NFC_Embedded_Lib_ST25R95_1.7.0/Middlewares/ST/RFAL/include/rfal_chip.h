@@ -384,6 +384,44 @@ ReturnCode rfalChipMeasureCombinedIQ( uint8_t* result );
  */
 ReturnCode rfalChipSetAntennaMode( bool single, bool rfiox );
 
+/*! 
+ *****************************************************************************
+ * \brief  Calibrate the ST25R95 Tag Detector
+ *  
+ * Runs the Tag Detector DAC calibration as per AN3433, the result becomes the
+ * reference used by RFAL_WUM_REFERENCE_AUTO
+ *
+ * \param[out] reference : calibrated DAC reference
+ *
+ * \return RFAL_ERR_WRONG_STATE  : RFAL not initialized or Wake-Up mode running
+ * \return RFAL_ERR_PARAM        : Invalid parameters
+ * \return RFAL_ERR_SYSTEM       : Calibration failed
+ * \return RFAL_ERR_NONE         : No error
+ *****************************************************************************
+ */
+ReturnCode rfalST25R95CalibrateTagDetector( uint8_t *reference );
+
+
+/*! 
+ *****************************************************************************
+ * \brief  Get the ST25R95 identification
+ *  
+ * Sends the IDN command and returns its data: the Device ID string
+ * followed by the ROM CRC
+ *
+ * \param[out] idn    : buffer to store the IDN data
+ * \param[in]  idnLen : size of idn
+ * \param[out] rcvLen : number of bytes stored in idn
+ *
+ * \return RFAL_ERR_WRONG_STATE  : RFAL not initialized or Wake-Up mode running
+ * \return RFAL_ERR_REQUEST      : An operation is ongoing
+ * \return RFAL_ERR_PARAM        : Invalid parameters
+ * \return RFAL_ERR_SYSTEM       : Command refused by the chip
+ * \return RFAL_ERR_NONE         : No error
+ *****************************************************************************
+ */
+ReturnCode rfalST25R95GetIdn( uint8_t *idn, uint8_t idnLen, uint8_t *rcvLen );
+
 #endif /* RFAL_CHIP_H */
 
 /**
@@ -803,6 +803,24 @@ ReturnCode rfalIsoDepATTRIB( const uint8_t* nfcid0, uint8_t PARAM1, rfalBitRate
 ReturnCode rfalIsoDepDeselect( void );
 
 
+/*! 
+ *****************************************************************************
+ *  \brief  Check Presence of the PICC
+ *
+ *  This function sends an R(NAK) with the current block number, as done by
+ *  the NFC Forum presence check, and waits for the R(ACK) of the PICC in a
+ *  blocking way. Must be called between two exchanges.
+ *
+ *  \return RFAL_ERR_WRONG_STATE : Not a Poller or an exchange is ongoing
+ *  \return RFAL_ERR_PROTO       : Unexpected response of the PICC
+ *  \return RFAL_ERR_TIMEOUT     : No response rcvd from PICC
+ *  \return RFAL_ERR_NONE        : PICC present
+ *
+ *****************************************************************************
+ */
+ReturnCode rfalIsoDepPollerCheckPresence( void );
+
+
 /*! 
  *****************************************************************************
  *  \brief  Start Deselect 
@@ -126,6 +126,7 @@
                                         ((dp))->totalDuration          = 1000U;                    \
                                         ((dp))->techs2Find             = RFAL_NFC_TECH_NONE;       \
                                         ((dp))->techs2Bail             = RFAL_NFC_TECH_NONE;       \
+                                        ((dp))->nfcbEndSlots           = RFAL_NFCB_SLOT_NUM_16;    \
                                         }
 
 /*
@@ -262,6 +263,10 @@ typedef struct{
     rfalWakeUpConfig       wakeupConfig;                     /*!< Wake-Up mode configuration                                         */
     bool                   wakeupPollBefore;                 /*!< Flag to Poll wakeupNPolls times before entering Wake-up            */
     uint16_t               wakeupNPolls;                     /*!< Number of polling cycles before|after entering Wake-up             */
+    /* Local patch (rfal crate): not part of ST's rfalNfcDiscoverParam, appended to keep the offsets of ST's members */
+    rfalNfcbSlots          nfcbInitSlots;                    /*!< Initial number of slots for NFC-B Collision Resolution             */
+    rfalNfcbSlots          nfcbEndSlots;                     /*!< Max number of slots for NFC-B Collision Resolution                 */
+    /* End of local patch */
 }rfalNfcDiscoverParam;
 
 
@@ -357,6 +362,22 @@ rfalNfcState rfalNfcGetState( void );
 ReturnCode rfalNfcGetDevicesFound( rfalNfcDevice **devList, uint8_t *devCnt );
 
 
+/* Local patch (rfal crate): not part of ST's RFAL */
+/*!
+ *****************************************************************************
+ * \brief  RFAL NFC Get NFC-B Collision Pending
+ *  
+ * It returns whether NFC-B devices still collided with the max number of
+ * slots in the last collision resolution, and were not found.
+ *
+ * \return true  : collision pending, more devices may be in the field
+ * \return false : no collision pending
+ *****************************************************************************
+ */
+bool rfalNfcGetNfcbCollisionPending( void );
+/* End of local patch */
+
+
 /*!
  *****************************************************************************
  * \brief  RFAL NFC Get Active Device
@@ -1076,6 +1076,41 @@ ReturnCode rfalIsoDepGetDeselectStatus( void )
     return ret;
 }
 
+
+/*******************************************************************************/
+ReturnCode rfalIsoDepPollerCheckPresence( void )
+{
+    ReturnCode ret;
+    uint8_t    txBuf[RFAL_ISODEP_PCB_LEN + RFAL_ISODEP_DID_LEN];
+    uint8_t    rxBuf[RFAL_ISODEP_PCB_LEN + RFAL_ISODEP_DID_LEN + ISODEP_CRC_LEN];
+    uint16_t   txLen;
+    uint16_t   rcvLen;
+    
+    /* Only between two exchanges as a Poller */
+    if( (gIsoDep.role != ISODEP_ROLE_PCD) || (gIsoDep.state != ISODEP_ST_IDLE) )
+    {
+        return RFAL_ERR_WRONG_STATE;
+    }
+    
+    /* R(NAK) with the current block number, the PICC must answer with an R(ACK) */
+    txLen          = 0;
+    txBuf[txLen++] = rfalIsoDep_PCBRNAK( gIsoDep.blockNumber );
+    if( (gIsoDep.did != RFAL_ISODEP_NO_DID) && (gIsoDep.did != RFAL_ISODEP_DID_00) )
+    {
+        txBuf[ISODEP_PCB_POS] |= ISODEP_PCB_DID_BIT;
+        txBuf[txLen++]         = gIsoDep.did;
+    }
+    
+    RFAL_EXIT_ON_ERR( ret, rfalTransceiveBlockingTxRx( txBuf, txLen, rxBuf, sizeof(rxBuf), &rcvLen, RFAL_TXRX_FLAGS_DEFAULT, (gIsoDep.fwt + gIsoDep.dFwt) ) );
+    
+    if( (rcvLen < RFAL_ISODEP_PCB_LEN) || (!rfalIsoDep_PCBisRACK( rxBuf[ISODEP_PCB_POS] )) )
+    {
+        return RFAL_ERR_PROTO;
+    }
+    
+    return RFAL_ERR_NONE;
+}
+
 #endif /* RFAL_FEATURE_ISO_DEP_POLL */
 
 
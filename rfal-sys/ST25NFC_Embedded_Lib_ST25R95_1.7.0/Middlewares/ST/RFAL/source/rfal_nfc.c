@@ -101,6 +101,7 @@ typedef struct{
     bool                    isTechInit;         /*!< Flag indicating technology has been set         */
     bool                    isOperOngoing;      /*!< Flag indicating operation is ongoing            */
     bool                    isDeactivating;     /*!< Flag indicating deactivation is ongoing         */
+    bool                    nfcbColPending;     /*!< Local patch (rfal crate): NFC-B collision pending */
 
     rfalNfcaSensRes         sensRes;            /*!< SENS_RES during card detection and activation   */
     rfalNfcbSensbRes        sensbRes;           /*!< SENSB_RES during card detection and activation  */
@@ -330,6 +331,13 @@ ReturnCode rfalNfcGetDevicesFound( rfalNfcDevice **devList, uint8_t *devCnt )
     return RFAL_ERR_NONE;
 }
 
+/*******************************************************************************/
+/* Local patch (rfal crate): not part of ST's RFAL */
+bool rfalNfcGetNfcbCollisionPending( void )
+{
+    return gNfcDev.nfcbColPending;
+}
+
 /*******************************************************************************/
 ReturnCode rfalNfcGetActiveDevice( rfalNfcDevice **dev )
 {
@@ -381,6 +389,7 @@ void rfalNfcWorker( void )
             gNfcDev.techs2do       = gNfcDev.disc.techs2Find;
             gNfcDev.state          = RFAL_NFC_STATE_POLL_TECHDETECT;
             gNfcDev.isDeactivating = false;
+            gNfcDev.nfcbColPending = false;                                          /* Local patch (rfal crate) */
         
             /* Start total duration timer */
             platformTimerDestroy( gNfcDev.discTmr );
@@ -1283,7 +1292,7 @@ static ReturnCode rfalNfcPollCollResolution( void )
         
         if( !gNfcDev.isOperOngoing )
         {
-            RFAL_EXIT_ON_ERR( err, rfalNfcbPollerStartCollisionResolution( gNfcDev.disc.compMode, (gNfcDev.disc.devLimit - gNfcDev.devCnt), nfcbDevList, &devCnt ) );
+            RFAL_EXIT_ON_ERR( err, rfalNfcbPollerStartSlottedCollisionResolution( gNfcDev.disc.compMode, (gNfcDev.disc.devLimit - gNfcDev.devCnt), gNfcDev.disc.nfcbInitSlots, gNfcDev.disc.nfcbEndSlots, nfcbDevList, &devCnt, &gNfcDev.nfcbColPending ) );  /* Local patch (rfal crate): slots from the discovery params, pending collisions kept */
          
             gNfcDev.isOperOngoing = true;
             return RFAL_ERR_BUSY;
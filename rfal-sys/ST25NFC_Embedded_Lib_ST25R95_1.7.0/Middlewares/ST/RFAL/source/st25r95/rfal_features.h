@@ -47,9 +47,9 @@
 */
 
 #define RFAL_SUPPORT_MODE_POLL_NFCA                true          /*!< RFAL Poll NFCA mode support switch    */
-#define RFAL_SUPPORT_MODE_POLL_NFCB                false         /*!< RFAL Poll NFCB mode support switch    */
-#define RFAL_SUPPORT_MODE_POLL_NFCF                false         /*!< RFAL Poll NFCF mode support switch    */
-#define RFAL_SUPPORT_MODE_POLL_NFCV                false         /*!< RFAL Poll NFCV mode support switch    */
+#define RFAL_SUPPORT_MODE_POLL_NFCB                true          /*!< RFAL Poll NFCB mode support switch    */
+#define RFAL_SUPPORT_MODE_POLL_NFCF                true          /*!< RFAL Poll NFCF mode support switch    */
+#define RFAL_SUPPORT_MODE_POLL_NFCV                true          /*!< RFAL Poll NFCV mode support switch    */
 #define RFAL_SUPPORT_MODE_POLL_ACTIVE_P2P          false         /*!< RFAL Poll AP2P mode support switch    */
 #define RFAL_SUPPORT_MODE_LISTEN_NFCA              true          /*!< RFAL Listen NFCA mode support switch  */
 #define RFAL_SUPPORT_MODE_LISTEN_NFCB              false         /*!< RFAL Listen NFCB mode support switch  */
@@ -115,7 +115,14 @@
 /*! RFAL Wake-Up Period/Timer */
 typedef enum 
 {
+    RFAL_WUM_PERIOD_100MS     = 0x10,     /*!< Wake-Up timer 100ms                                        */
+    RFAL_WUM_PERIOD_200MS     = 0x11,     /*!< Wake-Up timer 200ms                                        */
     RFAL_WUM_PERIOD_300MS     = 0x12,     /*!< Wake-Up timer 300ms                                        */
+    RFAL_WUM_PERIOD_400MS     = 0x13,     /*!< Wake-Up timer 400ms                                        */
+    RFAL_WUM_PERIOD_500MS     = 0x14,     /*!< Wake-Up timer 500ms                                        */
+    RFAL_WUM_PERIOD_600MS     = 0x15,     /*!< Wake-Up timer 600ms                                        */
+    RFAL_WUM_PERIOD_700MS     = 0x16,     /*!< Wake-Up timer 700ms                                        */
+    RFAL_WUM_PERIOD_800MS     = 0x17,     /*!< Wake-Up timer 800ms                                        */
 } rfalWumPeriod;
 
 
@@ -44,6 +44,7 @@
 #include "rfal_nfcf.h"
 #include "rfal_nfca.h"
 #include "rfal_analogConfig.h"
+#include "rfal_chip.h"
 #include <string.h>
 
 /*
@@ -198,7 +199,7 @@ typedef enum
 #define RFAL_ST25R95_ISO14443A_SPLITFRAME                                            0x40U /*!< Transmission flags bit 6: SplitFrame        */
 #define RFAL_ST25R95_ISO14443A_TOPAZFORMAT                                           0x80U /*!< Transmission flags bit 7: Topaz send format */
 
-#define RFAL_ST25R95_IDLE_DEFAULT_WUPERIOD                                           0x24U /*!< Fixed WU Period to reach ~300 ms timeout with Max Sleep = 0 */
+#define RFAL_ST25R95_IDLE_WUPERIOD(ms)                     (((((uint32_t)(ms)) + 4U) / 8U) - 2U) /*!< WU Period of a ms timeout with Max Sleep = 0, (WUPeriod + 2) x 256 LFO cycles of ~8 ms */
 
 /*
 ******************************************************************************
@@ -308,6 +309,74 @@ ReturnCode rfalCalibrate(void)
     return (RFAL_ERR_NONE);
 }
 
+/*******************************************************************************/
+ReturnCode rfalST25R95CalibrateTagDetector(uint8_t *reference)
+{
+    uint8_t cal;
+
+    if( reference == NULL )
+    {
+        return RFAL_ERR_PARAM;
+    }
+
+    if( (gRFAL.state < RFAL_STATE_INIT) || (gRFAL.state == RFAL_STATE_WUM) )
+    {
+        return RFAL_ERR_WRONG_STATE;
+    }
+
+    cal = st25r95CalibrateTagDetector();
+    if( cal == 0xFFU )
+    {
+        return RFAL_ERR_SYSTEM;
+    }
+
+    /* New reference used by RFAL_WUM_REFERENCE_AUTO */
+    gRFAL.wum.CalTagDet = cal;
+    *reference          = cal;
+
+    return RFAL_ERR_NONE;
+}
+
+/*******************************************************************************/
+ReturnCode rfalST25R95GetIdn(uint8_t *idn, uint8_t idnLen, uint8_t *rcvLen)
+{
+    ReturnCode ret;
+    uint8_t    cmd[] = {ST25R95_COMMAND_IDN, 0x00U};
+    uint8_t    respBuffer[ST25R95_IDN_RESPONSE_BUFLEN];
+
+    if( (idn == NULL) || (rcvLen == NULL) )
+    {
+        return RFAL_ERR_PARAM;
+    }
+
+    /* Ensure that no previous operation is still ongoing */
+    if( rfalChipIsBusy() )
+    {
+        return RFAL_ERR_REQUEST;
+    }
+
+    if( (gRFAL.state < RFAL_STATE_INIT) || (gRFAL.state == RFAL_STATE_WUM) )
+    {
+        return RFAL_ERR_WRONG_STATE;
+    }
+
+    ret = st25r95SendCommandTypeAndLen(cmd, respBuffer, ST25R95_IDN_RESPONSE_BUFLEN);
+    if( ret != RFAL_ERR_NONE )
+    {
+        return ret;
+    }
+    if( respBuffer[ST25R95_CMD_RESULT_OFFSET] != ST25R95_ERRCODE_NONE )
+    {
+        return RFAL_ERR_SYSTEM;
+    }
+
+    /* Device ID and ROM CRC */
+    *rcvLen = RFAL_MIN(RFAL_MIN(respBuffer[ST25R95_CMD_LENGTH_OFFSET], idnLen), (ST25R95_IDN_RESPONSE_BUFLEN - ST25R95_CMD_DATA_OFFSET));
+    RFAL_MEMCPY(idn, &respBuffer[ST25R95_CMD_DATA_OFFSET], *rcvLen);
+
+    return RFAL_ERR_NONE;
+}
+
 /*******************************************************************************/
 ReturnCode rfalAdjustRegulators(uint16_t* result)
 {
@@ -1804,9 +1873,14 @@ ReturnCode rfalWakeUpModeStart( const rfalWakeUpConfig *config )
     {
         return RFAL_ERR_PARAM;
     }
+    
+    if ((gRFAL.wum.cfg.period < RFAL_WUM_PERIOD_100MS) || (gRFAL.wum.cfg.period > RFAL_WUM_PERIOD_800MS))
+    {
+        return RFAL_ERR_PARAM;
+    }
 
-    /* Use a fixed period of ~300 ms */
-    st25r95Idle(gRFAL.wum.cfg.indAmp.reference - gRFAL.wum.cfg.indAmp.delta, gRFAL.wum.cfg.indAmp.reference + gRFAL.wum.cfg.indAmp.delta, RFAL_ST25R95_IDLE_DEFAULT_WUPERIOD);
+    /* Periods are 100 ms apart from RFAL_WUM_PERIOD_100MS */
+    st25r95Idle(gRFAL.wum.cfg.indAmp.reference - gRFAL.wum.cfg.indAmp.delta, gRFAL.wum.cfg.indAmp.reference + gRFAL.wum.cfg.indAmp.delta, (uint8_t)RFAL_ST25R95_IDLE_WUPERIOD((((uint32_t)gRFAL.wum.cfg.period - (uint32_t)RFAL_WUM_PERIOD_100MS) + 1U) * 100U));
     gRFAL.state     = RFAL_STATE_WUM;
     gRFAL.wum.state = RFAL_WUM_STATE_ENABLED;
     return RFAL_ERR_NONE;
@@ -37,6 +37,7 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
         .file(format!("{src_dir}/RFAL/source/rfal_st25xv.c"))
         .file(format!("{src_dir}/RFAL/source/rfal_analogConfig.c"))
         .file(format!("{src_dir}/RFAL/source/rfal_crc.c"))
+        .file(format!("{src_dir}/RFAL/source/rfal_dpo.c"))
         .file(format!("{src_dir}/RFAL/source/rfal_iso15693_2.c"))
         .file(format!("{src_dir}/RFAL/source/rfal_nfc.c"))
         .file(format!("{src_dir}/RFAL/source/rfal_nfca.c"))
@@ -49,20 +50,20 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
         .file(format!("{src_dir}/RFAL/source/rfal_t2t.c"))
         .file(format!("{src_dir}/RFAL/source/rfal_t4t.c"))
         .file(format!("{src_dir}/NDEF/source/message/ndef_record.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_types.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_aar.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_bluetooth.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_deviceinfo.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_empty.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_flat.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_media.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_text.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_tnep.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_uri.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_vcard.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_wifi.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_wlc.c"))
-        // .file(format!("{src_dir}/NDEF/source/message/ndef_type_wpcwlc.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_types.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_aar.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_bluetooth.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_deviceinfo.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_empty.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_flat.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_media.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_text.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_tnep.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_uri.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_vcard.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_wifi.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_wlc.c"))
+        .file(format!("{src_dir}/NDEF/source/message/ndef_type_wpcwlc.c"))
         .file(format!("{src_dir}/NDEF/source/message/ndef_message.c"))
         .file(format!("{src_dir}/NDEF/source/poller/ndef_t2t.c"))
         .file(format!("{src_dir}/NDEF/source/poller/ndef_t3t.c"))
@@ -118,29 +119,40 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     bindgen::Builder::default()
         .header(format!("{src_dir}/RFAL/include/rfal_utils.h"))
+        .header(format!("{src_dir}/RFAL/include/rfal_analogConfig.h"))
+        .header(format!("{src_dir}/RFAL/include/rfal_chip.h"))
+        .header(format!("{src_dir}/RFAL/include/rfal_dpo.h"))
         .header(format!("{src_dir}/RFAL/include/rfal_nfc.h"))
         .header(format!("{src_dir}/RFAL/include/rfal_nfca.h"))
         .header(format!("{src_dir}/RFAL/include/rfal_nfcb.h"))
+        .header(format!("{src_dir}/RFAL/include/rfal_nfcf.h"))
+        .header(format!("{src_dir}/RFAL/include/rfal_nfcv.h"))
         .header(format!("{src_dir}/RFAL/include/rfal_rf.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_buffer.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_record.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_message.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_aar.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_bluetooth.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_deviceinfo.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_empty.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_flat.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_media.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_text.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_tnep.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_uri.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_vcard.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_wifi.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_wlc.h"))
-        // .header(format!("{src_dir}/NDEF/include/message/ndef_type_wpcwlc.h"))
+        .header(format!("{src_dir}/RFAL/include/rfal_st25tb.h"))
+        .header(format!("{src_dir}/RFAL/include/rfal_st25xv.h"))
+        .header(format!("{src_dir}/RFAL/include/rfal_t1t.h"))
+        .header(format!("{src_dir}/RFAL/include/rfal_t2t.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_buffer.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_record.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_message.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_aar.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_bluetooth.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_deviceinfo.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_empty.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_flat.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_media.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_text.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_tnep.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_uri.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_vcard.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_wifi.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_wlc.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_type_wpcwlc.h"))
+        .header(format!("{src_dir}/NDEF/include/message/ndef_types.h"))
         .header(format!("{src_dir}/NDEF/include/poller/ndef_poller.h"))
         .rustified_enum("ndefDeviceType")
         .rustified_enum("ndefState")
+        .rustified_enum("ndefTypeId")
         .rustified_enum("rfal14443AShortFrameCmd")
         .rustified_enum("rfalBitRate")
         .rustified_enum("rfalComplianceMode")
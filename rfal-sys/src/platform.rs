@@ -151,7 +151,7 @@ pub fn ffi_get_ticks_ms() -> u32 {
 }
 
 #[no_mangle]
-fn ffi_delay_ms(delay: u32) {
+pub fn ffi_delay_ms(delay: u32) {
     unsafe {
         (RFAL_PLATFORM
             .as_ref()
@@ -0,0 +1,49 @@
+/******************************************************************************
+  * @attention
+  *
+  * COPYRIGHT 2018 STMicroelectronics, all rights reserved
+  *
+  * Unless required by applicable law or agreed to in writing, software
+  * distributed under the License is distributed on an "AS IS" BASIS,
+  * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied,
+  * AND SPECIFICALLY DISCLAIMING THE IMPLIED WARRANTIES OF MERCHANTABILITY,
+  * FITNESS FOR A PARTICULAR PURPOSE, AND NON-INFRINGEMENT.
+  * See the License for the specific language governing permissions and
+  * limitations under the License.
+  *
+******************************************************************************/
+
+/*! \file
+ *
+ *  \brief RF Dynamic Power Table default values
+ *
+ *  The ST25R95 has no adjustable RFO resistance, the level of the entry
+ *  selected is only applied through the DPO analog configurations.
+ *
+ */
+
+#ifndef RFAL_DPO_TBL_H
+#define RFAL_DPO_TBL_H
+
+/*
+******************************************************************************
+* INCLUDES
+******************************************************************************
+*/
+#include "rfal_dpo.h"
+
+/*
+******************************************************************************
+* GLOBAL DATA TYPES
+******************************************************************************
+*/
+
+/*! Default DPO table */
+const rfalDpoEntry rfalDpoDefaultSettings [] = {
+                { 0x00, 255, 200 },
+                { 0x01, 210, 150 },
+                { 0x02, 160, 100 },
+                { 0x03, 110, 50  },
+};
+
+#endif /* RFAL_DPO_TBL_H */
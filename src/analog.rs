@@ -0,0 +1,287 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{result, Error, Result};
+use rfal_sys::rfalAnalogConfig;
+
+/// Maximum size of the analog configuration table, in bytes.
+pub const ANALOG_CONFIG_TBL_SIZE: usize = rfal_sys::RFAL_ANALOG_CONFIG_TBL_SIZE as usize;
+/// Maximum number of register settings of one configuration ID.
+pub const ANALOG_CONFIG_MAX_SETTINGS: usize = (u8::MAX as usize - HEADER_LEN) / SETTING_LEN;
+
+// ID (big endian) and number of settings, then the settings
+const HEADER_LEN: usize = 3;
+// register address (big endian), mask and value
+const SETTING_LEN: usize = 4;
+// DPO power level in the 2 upper technology bits
+const DPO_LEVEL_SHIFT: u16 = 13;
+
+/// Whether the settings apply when polling or listening.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnalogMode {
+    Poll,
+    Listen,
+}
+
+/// Technology of the settings, `Chip` for the chip events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnalogTech {
+    Chip,
+    Nfca,
+    Nfcb,
+    Nfcf,
+    Ap2p,
+    Nfcv,
+}
+
+/// Bit rate of the settings, `Common` applies to all of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnalogBitRate {
+    Common,
+    Br106,
+    Br212,
+    Br424,
+    Br848,
+    Br1695,
+    Br3390,
+    Br6780,
+    Br211p88,
+    Br105p94,
+    Br53,
+    Br26,
+    Br1p6,
+}
+
+/// When the settings apply.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnalogDirection {
+    None,
+    Tx,
+    Rx,
+    Anticollision,
+    Dpo,
+    Dlma,
+}
+
+/// Chip events with their own settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnalogChipEvent {
+    Init,
+    Deinit,
+    FieldOn,
+    FieldOff,
+    WakeUpOn,
+    WakeUpOff,
+    ListenOn,
+    ListenOff,
+    PollCommon,
+    ListenCommon,
+    LowPowerOn,
+    LowPowerOff,
+    /// DPO or WLC power level, 0 to 15.
+    PowerLevel(u8),
+}
+
+/// Analog configuration ID, selecting when a list of settings is applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AnalogConfigId(pub u16);
+
+impl AnalogConfigId {
+    pub fn new(
+        mode: AnalogMode,
+        tech: AnalogTech,
+        bit_rate: AnalogBitRate,
+        direction: AnalogDirection,
+    ) -> Self {
+        let mode = match mode {
+            AnalogMode::Poll => rfal_sys::RFAL_ANALOG_CONFIG_POLL,
+            AnalogMode::Listen => rfal_sys::RFAL_ANALOG_CONFIG_LISTEN,
+        };
+        let tech = match tech {
+            AnalogTech::Chip => rfal_sys::RFAL_ANALOG_CONFIG_TECH_CHIP,
+            AnalogTech::Nfca => rfal_sys::RFAL_ANALOG_CONFIG_TECH_NFCA,
+            AnalogTech::Nfcb => rfal_sys::RFAL_ANALOG_CONFIG_TECH_NFCB,
+            AnalogTech::Nfcf => rfal_sys::RFAL_ANALOG_CONFIG_TECH_NFCF,
+            AnalogTech::Ap2p => rfal_sys::RFAL_ANALOG_CONFIG_TECH_AP2P,
+            AnalogTech::Nfcv => rfal_sys::RFAL_ANALOG_CONFIG_TECH_NFCV,
+        };
+        let bit_rate = match bit_rate {
+            AnalogBitRate::Common => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_COMMON,
+            AnalogBitRate::Br106 => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_106,
+            AnalogBitRate::Br212 => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_212,
+            AnalogBitRate::Br424 => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_424,
+            AnalogBitRate::Br848 => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_848,
+            AnalogBitRate::Br1695 => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_1695,
+            AnalogBitRate::Br3390 => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_3390,
+            AnalogBitRate::Br6780 => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_6780,
+            AnalogBitRate::Br211p88 => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_211p88,
+            AnalogBitRate::Br105p94 => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_105p94,
+            AnalogBitRate::Br53 => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_53,
+            AnalogBitRate::Br26 => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_26,
+            AnalogBitRate::Br1p6 => rfal_sys::RFAL_ANALOG_CONFIG_BITRATE_1p6,
+        };
+        let direction = match direction {
+            AnalogDirection::None => rfal_sys::RFAL_ANALOG_CONFIG_NO_DIRECTION,
+            AnalogDirection::Tx => rfal_sys::RFAL_ANALOG_CONFIG_TX,
+            AnalogDirection::Rx => rfal_sys::RFAL_ANALOG_CONFIG_RX,
+            AnalogDirection::Anticollision => rfal_sys::RFAL_ANALOG_CONFIG_ANTICOL,
+            AnalogDirection::Dpo => rfal_sys::RFAL_ANALOG_CONFIG_DPO,
+            AnalogDirection::Dlma => rfal_sys::RFAL_ANALOG_CONFIG_DLMA,
+        };
+        Self((mode | tech | bit_rate | direction) as u16)
+    }
+    /// ID of the settings applied on a chip `event`.
+    pub fn chip(mode: AnalogMode, event: AnalogChipEvent) -> Self {
+        let event = match event {
+            AnalogChipEvent::Init => rfal_sys::RFAL_ANALOG_CONFIG_CHIP_INIT,
+            AnalogChipEvent::Deinit => rfal_sys::RFAL_ANALOG_CONFIG_CHIP_DEINIT,
+            AnalogChipEvent::FieldOn => rfal_sys::RFAL_ANALOG_CONFIG_CHIP_FIELD_ON,
+            AnalogChipEvent::FieldOff => rfal_sys::RFAL_ANALOG_CONFIG_CHIP_FIELD_OFF,
+            AnalogChipEvent::WakeUpOn => rfal_sys::RFAL_ANALOG_CONFIG_CHIP_WAKEUP_ON,
+            AnalogChipEvent::WakeUpOff => rfal_sys::RFAL_ANALOG_CONFIG_CHIP_WAKEUP_OFF,
+            AnalogChipEvent::ListenOn => rfal_sys::RFAL_ANALOG_CONFIG_CHIP_LISTEN_ON,
+            AnalogChipEvent::ListenOff => rfal_sys::RFAL_ANALOG_CONFIG_CHIP_LISTEN_OFF,
+            AnalogChipEvent::PollCommon => rfal_sys::RFAL_ANALOG_CONFIG_CHIP_POLL_COMMON,
+            AnalogChipEvent::ListenCommon => rfal_sys::RFAL_ANALOG_CONFIG_CHIP_LISTEN_COMMON,
+            AnalogChipEvent::LowPowerOn => rfal_sys::RFAL_ANALOG_CONFIG_CHIP_LOWPOWER_ON,
+            AnalogChipEvent::LowPowerOff => rfal_sys::RFAL_ANALOG_CONFIG_CHIP_LOWPOWER_OFF,
+            AnalogChipEvent::PowerLevel(level) => {
+                rfal_sys::RFAL_ANALOG_CONFIG_CHIP_POWER_LVL_00 + (level & 0x0F) as u32
+            }
+        };
+        let mode = match mode {
+            AnalogMode::Poll => rfal_sys::RFAL_ANALOG_CONFIG_POLL,
+            AnalogMode::Listen => rfal_sys::RFAL_ANALOG_CONFIG_LISTEN,
+        };
+        Self((mode | rfal_sys::RFAL_ANALOG_CONFIG_TECH_CHIP | event) as u16)
+    }
+    /// Adds the DPO power level, 0 to 3, to an ID with [`AnalogDirection::Dpo`].
+    pub fn with_dpo_level(self, level: u8) -> Self {
+        Self(self.0 | (((level & 0x03) as u16) << DPO_LEVEL_SHIFT))
+    }
+}
+
+/// Register-mask-value setting, only the bits in `mask` are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AnalogRegSetting {
+    pub addr: u16,
+    pub mask: u8,
+    pub value: u8,
+}
+
+/// Runtime analog configuration, replacing the table built in the C library.
+/// RFAL reverts to the built-in table on [`crate::Nfc::initialize`].
+pub struct AnalogConfig;
+
+impl AnalogConfig {
+    pub fn is_ready() -> bool {
+        unsafe { rfal_sys::rfalAnalogConfigIsReady() }
+    }
+    /// Restores the built-in table.
+    pub fn reset() {
+        unsafe { rfal_sys::rfalAnalogConfigInitialize() }
+    }
+    /// Appends the `settings` of `id` to the table, the previous table is
+    /// discarded on the first call and the new one applies once `more` is false.
+    pub fn write_list(id: AnalogConfigId, settings: &[AnalogRegSetting], more: bool) -> Result<()> {
+        if settings.len() > ANALOG_CONFIG_MAX_SETTINGS {
+            return Err(Error::Param);
+        }
+        let mut buf = [0u8; HEADER_LEN + ANALOG_CONFIG_MAX_SETTINGS * SETTING_LEN];
+        buf[..2].copy_from_slice(&id.0.to_be_bytes());
+        buf[2] = settings.len() as u8;
+        for (chunk, setting) in buf[HEADER_LEN..]
+            .chunks_exact_mut(SETTING_LEN)
+            .zip(settings)
+        {
+            chunk[..2].copy_from_slice(&setting.addr.to_be_bytes());
+            chunk[2] = setting.mask;
+            chunk[3] = setting.value;
+        }
+        let more = if more {
+            rfal_sys::RFAL_ANALOG_CONFIG_UPDATE_MORE
+        } else {
+            rfal_sys::RFAL_ANALOG_CONFIG_UPDATE_LAST
+        };
+        result(unsafe {
+            rfal_sys::rfalAnalogConfigListWrite(more as u8, buf.as_ptr() as *const rfalAnalogConfig)
+        })
+    }
+    /// Reads the entry at `offset` of `table`, a copy of the current table taken
+    /// once with [`AnalogConfig::read_raw`], into `settings`, advances `offset` to
+    /// the next entry and returns its ID, the number of settings and whether more
+    /// entries follow. Fails with `Error::Param` if the entry at `offset` does
+    /// not fit in `table`.
+    pub fn read_list(
+        table: &[u8],
+        offset: &mut u16,
+        settings: &mut [AnalogRegSetting],
+    ) -> Result<(AnalogConfigId, usize, bool)> {
+        // rfalAnalogConfigListRead trusts the offset, read the copy instead
+        let pos = *offset as usize;
+        let rest = table.get(pos..).ok_or(Error::Param)?;
+        let entry = &rest[..entry_len(rest)?];
+        let num = entry[2] as usize;
+        if num > settings.len() {
+            return Err(Error::NoMem);
+        }
+        for (setting, chunk) in settings
+            .iter_mut()
+            .zip(entry[HEADER_LEN..].chunks_exact(SETTING_LEN))
+        {
+            *setting = AnalogRegSetting {
+                addr: u16::from_be_bytes([chunk[0], chunk[1]]),
+                mask: chunk[2],
+                value: chunk[3],
+            };
+        }
+        *offset += entry.len() as u16;
+        Ok((
+            AnalogConfigId(u16::from_be_bytes([entry[0], entry[1]])),
+            num,
+            (*offset as usize) < table.len(),
+        ))
+    }
+    /// Replaces the whole table with `table`, in the raw RFAL format.
+    pub fn write_raw(table: &[u8]) -> Result<()> {
+        if table.len() > u16::MAX as usize {
+            return Err(Error::NoMem);
+        }
+        result(unsafe {
+            rfal_sys::rfalAnalogConfigListWriteRaw(table.as_ptr(), table.len() as u16)
+        })
+    }
+    /// Copies the current table into `buf` in the raw RFAL format, returns its size.
+    pub fn read_raw(buf: &mut [u8]) -> Result<usize> {
+        let mut size = 0u16;
+        result(unsafe {
+            rfal_sys::rfalAnalogConfigListReadRaw(
+                buf.as_mut_ptr(),
+                buf.len().min(u16::MAX as usize) as u16,
+                &mut size,
+            )
+        })?;
+        Ok(size as usize)
+    }
+    /// Checks and loads a table serialized by [`AnalogConfig::read_raw`], for
+    /// instance stored in flash by the production test.
+    pub fn load(blob: &[u8]) -> Result<()> {
+        let mut rest = blob;
+        while !rest.is_empty() {
+            rest = &rest[entry_len(rest)?..];
+        }
+        Self::write_raw(blob)
+    }
+}
+
+// length of the entry at the start of `table`, checking it fits in
+fn entry_len(table: &[u8]) -> Result<usize> {
+    if table.len() < HEADER_LEN {
+        return Err(Error::Param);
+    }
+    let len = HEADER_LEN + table[2] as usize * SETTING_LEN;
+    if table.len() < len {
+        return Err(Error::Param);
+    }
+    Ok(len)
+}
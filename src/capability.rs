@@ -0,0 +1,450 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{
+    ndefCapabilityContainer, ndefCapabilityContainerT1T, ndefCapabilityContainerT2T,
+    ndefDeviceType, ndefInfo, ndefState, Error, Result,
+};
+use rfal_sys::{ndefAttribInfoBlockT3T, ndefCapabilityContainerT4T, ndefCapabilityContainerT5T};
+
+const T1T_T2T_MAGIC: u8 = 0xE1;
+
+/// Mapping version of a capability container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Version {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl Version {
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+    fn is_valid(&self) -> bool {
+        self.major <= 0xF && self.minor <= 0xF
+    }
+}
+
+/// NDEF read or write access condition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    Always,
+    Never,
+    /// RFU or proprietary value, as found on the tag.
+    Proprietary(u8),
+}
+
+impl Access {
+    fn from_raw(value: u8, never: u8) -> Self {
+        match value {
+            0 => Access::Always,
+            v if v == never => Access::Never,
+            v => Access::Proprietary(v),
+        }
+    }
+    fn to_raw(self, never: u8) -> Result<u8> {
+        match self {
+            Access::Always => Ok(0),
+            Access::Never => Ok(never),
+            Access::Proprietary(v) if v != 0 && v < never => Ok(v),
+            Access::Proprietary(_) => Err(Error::Param),
+        }
+    }
+}
+
+/// Type 1 Tag capability container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct T1tCc {
+    pub version: Version,
+    /// TMS, the tag memory size is `8 * (tag_memory_size + 1)` bytes.
+    pub tag_memory_size: u16,
+    pub read: Access,
+    pub write: Access,
+}
+
+impl T1tCc {
+    const ACCESS_NEVER: u8 = 0xF;
+
+    fn from_raw(cc: &ndefCapabilityContainerT1T) -> Self {
+        Self {
+            version: Version::new(cc.majorVersion, cc.minorVersion),
+            tag_memory_size: cc.tagMemorySize,
+            read: Access::from_raw(cc.readAccess, Self::ACCESS_NEVER),
+            write: Access::from_raw(cc.writeAccess, Self::ACCESS_NEVER),
+        }
+    }
+    fn to_raw(self) -> Result<ndefCapabilityContainerT1T> {
+        if !self.version.is_valid() || self.version.major != 1 || self.tag_memory_size > 0xFF {
+            return Err(Error::Param);
+        }
+        Ok(ndefCapabilityContainerT1T {
+            magicNumber: T1T_T2T_MAGIC,
+            majorVersion: self.version.major,
+            minorVersion: self.version.minor,
+            tagMemorySize: self.tag_memory_size,
+            readAccess: self.read.to_raw(Self::ACCESS_NEVER)?,
+            writeAccess: self.write.to_raw(Self::ACCESS_NEVER)?,
+        })
+    }
+}
+
+/// Type 2 Tag capability container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct T2tCc {
+    pub version: Version,
+    /// The data area is `8 * size` bytes.
+    pub size: u8,
+    pub read: Access,
+    pub write: Access,
+}
+
+impl T2tCc {
+    const ACCESS_NEVER: u8 = 0xF;
+
+    /// NFC Forum v1.0 container for a data area of `8 * size` bytes, read/write.
+    pub const fn new(size: u8) -> Self {
+        Self {
+            version: Version::new(1, 0),
+            size,
+            read: Access::Always,
+            write: Access::Always,
+        }
+    }
+    pub fn area_len(&self) -> u32 {
+        self.size as u32 * 8
+    }
+    fn from_raw(cc: &ndefCapabilityContainerT2T) -> Self {
+        Self {
+            version: Version::new(cc.majorVersion, cc.minorVersion),
+            size: cc.size,
+            read: Access::from_raw(cc.readAccess, Self::ACCESS_NEVER),
+            write: Access::from_raw(cc.writeAccess, Self::ACCESS_NEVER),
+        }
+    }
+    fn to_raw(self) -> Result<ndefCapabilityContainerT2T> {
+        if !self.version.is_valid() || self.version.major != 1 || self.size == 0 {
+            return Err(Error::Param);
+        }
+        Ok(ndefCapabilityContainerT2T {
+            magicNumber: T1T_T2T_MAGIC,
+            majorVersion: self.version.major,
+            minorVersion: self.version.minor,
+            size: self.size,
+            readAccess: self.read.to_raw(Self::ACCESS_NEVER)?,
+            writeAccess: self.write.to_raw(Self::ACCESS_NEVER)?,
+        })
+    }
+}
+
+/// Type 3 Tag attribute information block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct T3tAttributeInfo {
+    pub version: Version,
+    /// Nbr, blocks read with one CHECK command.
+    pub blocks_per_read: u8,
+    /// Nbw, blocks written with one UPDATE command.
+    pub blocks_per_write: u8,
+    /// NmaxB, blocks available for NDEF data.
+    pub max_blocks: u16,
+    /// WriteFlag set, a previous write was not completed.
+    pub write_in_progress: bool,
+    /// RWFlag
+    pub writable: bool,
+    /// Ln, length of the stored NDEF message.
+    pub message_len: u32,
+}
+
+impl T3tAttributeInfo {
+    const WRITE_FLAG_ON: u8 = 0xF;
+    const RW_FLAG_RW: u8 = 0x1;
+
+    fn from_raw(aib: &ndefAttribInfoBlockT3T) -> Self {
+        Self {
+            version: Version::new(aib.majorVersion, aib.minorVersion),
+            blocks_per_read: aib.nbR,
+            blocks_per_write: aib.nbW,
+            max_blocks: aib.nMaxB,
+            write_in_progress: aib.writeFlag == Self::WRITE_FLAG_ON,
+            writable: aib.rwFlag == Self::RW_FLAG_RW,
+            message_len: aib.Ln,
+        }
+    }
+    fn to_raw(self) -> Result<ndefAttribInfoBlockT3T> {
+        if !self.version.is_valid()
+            || self.version.major != 1
+            || self.blocks_per_read == 0
+            || self.blocks_per_write == 0
+            || self.max_blocks == 0
+            || self.message_len > 0xFF_FFFF
+        {
+            return Err(Error::Param);
+        }
+        Ok(ndefAttribInfoBlockT3T {
+            majorVersion: self.version.major,
+            minorVersion: self.version.minor,
+            nbR: self.blocks_per_read,
+            nbW: self.blocks_per_write,
+            nMaxB: self.max_blocks,
+            writeFlag: if self.write_in_progress {
+                Self::WRITE_FLAG_ON
+            } else {
+                0
+            },
+            rwFlag: if self.writable { Self::RW_FLAG_RW } else { 0 },
+            Ln: self.message_len,
+        })
+    }
+}
+
+/// Type 4 Tag capability container (mapping version 2.0 or 3.0).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct T4tCc {
+    pub version: Version,
+    /// MLe, maximum data read with one ReadBinary.
+    pub max_le: u16,
+    /// MLc, maximum data sent with one UpdateBinary.
+    pub max_lc: u16,
+    pub file_id: u16,
+    pub file_size: u32,
+    pub read: Access,
+    pub write: Access,
+}
+
+impl T4tCc {
+    const ACCESS_NEVER: u8 = 0xFF;
+    const CC_LEN_V2: u16 = 15;
+    const CC_LEN_V3: u16 = 17;
+
+    fn from_raw(cc: &ndefCapabilityContainerT4T) -> Self {
+        Self {
+            version: Version::new(cc.vNo >> 4, cc.vNo & 0xF),
+            max_le: cc.mLe,
+            max_lc: cc.mLc,
+            file_id: u16::from_be_bytes(cc.fileId),
+            file_size: cc.fileSize,
+            read: Access::from_raw(cc.readAccess, Self::ACCESS_NEVER),
+            write: Access::from_raw(cc.writeAccess, Self::ACCESS_NEVER),
+        }
+    }
+    fn to_raw(self) -> Result<ndefCapabilityContainerT4T> {
+        let (cc_len, max_file_size) = match self.version.major {
+            2 => (Self::CC_LEN_V2, 0x7FFF),
+            3 => (Self::CC_LEN_V3, 0xFFFF_FFFE),
+            _ => return Err(Error::Param),
+        };
+        if !self.version.is_valid()
+            || self.max_le < 0x000F
+            || self.max_lc == 0
+            || !(0x0005..=max_file_size).contains(&self.file_size)
+        {
+            return Err(Error::Param);
+        }
+        Ok(ndefCapabilityContainerT4T {
+            ccLen: cc_len,
+            vNo: self.version.major << 4 | self.version.minor,
+            mLe: self.max_le,
+            mLc: self.max_lc,
+            fileId: self.file_id.to_be_bytes(),
+            fileSize: self.file_size,
+            readAccess: self.read.to_raw(Self::ACCESS_NEVER)?,
+            writeAccess: self.write.to_raw(Self::ACCESS_NEVER)?,
+        })
+    }
+}
+
+/// Type 5 Tag capability container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct T5tCc {
+    pub version: Version,
+    /// 8 bytes CC (MLEN on 2 bytes) instead of 4 bytes.
+    pub extended: bool,
+    /// Magic number E2h, blocks are addressed on 2 bytes.
+    pub two_byte_address: bool,
+    pub read: Access,
+    pub write: Access,
+    /// MLEN, the data area is `8 * memory_len` bytes.
+    pub memory_len: u16,
+    /// Use special frames for write-alike commands.
+    pub special_frame: bool,
+    /// (EXTENDED_)LOCK_SINGLE_BLOCK supported.
+    pub lock_block: bool,
+    /// Memory larger than 2040 bytes described with a 4 bytes CC (Android).
+    pub mlen_overflow: bool,
+    /// (EXTENDED_)READ_MULTIPLE_BLOCK supported.
+    pub multiple_block_read: bool,
+}
+
+impl T5tCc {
+    const ACCESS_NEVER: u8 = 0x3;
+    const MAGIC_1_BYTE_ADDR: u8 = 0xE1;
+    const MAGIC_2_BYTES_ADDR: u8 = 0xE2;
+    const CC_LEN_4_BYTES: u8 = 4;
+    const CC_LEN_8_BYTES: u8 = 8;
+
+    fn from_raw(cc: &ndefCapabilityContainerT5T) -> Self {
+        Self {
+            version: Version::new(cc.majorVersion, cc.minorVersion),
+            extended: cc.ccLen == Self::CC_LEN_8_BYTES,
+            two_byte_address: cc.magicNumber == Self::MAGIC_2_BYTES_ADDR,
+            read: Access::from_raw(cc.readAccess, Self::ACCESS_NEVER),
+            write: Access::from_raw(cc.writeAccess, Self::ACCESS_NEVER),
+            memory_len: cc.memoryLen,
+            special_frame: cc.specialFrame,
+            lock_block: cc.lockBlock,
+            mlen_overflow: cc.mlenOverflow,
+            multiple_block_read: cc.multipleBlockRead,
+        }
+    }
+    fn to_raw(self) -> Result<ndefCapabilityContainerT5T> {
+        if !self.version.is_valid()
+            || self.version.major != 1
+            || (!self.extended && self.memory_len > 0xFF)
+        {
+            return Err(Error::Param);
+        }
+        Ok(ndefCapabilityContainerT5T {
+            ccLen: if self.extended {
+                Self::CC_LEN_8_BYTES
+            } else {
+                Self::CC_LEN_4_BYTES
+            },
+            magicNumber: if self.two_byte_address {
+                Self::MAGIC_2_BYTES_ADDR
+            } else {
+                Self::MAGIC_1_BYTE_ADDR
+            },
+            majorVersion: self.version.major,
+            minorVersion: self.version.minor,
+            readAccess: self.read.to_raw(Self::ACCESS_NEVER)?,
+            writeAccess: self.write.to_raw(Self::ACCESS_NEVER)?,
+            memoryLen: self.memory_len,
+            specialFrame: self.special_frame,
+            lockBlock: self.lock_block,
+            mlenOverflow: self.mlen_overflow,
+            multipleBlockRead: self.multiple_block_read,
+        })
+    }
+}
+
+/// Capability container (or attribute information block) of an NDEF tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CapabilityContainer {
+    T1t(T1tCc),
+    T2t(T2tCc),
+    T3t(T3tAttributeInfo),
+    T4t(T4tCc),
+    T5t(T5tCc),
+}
+
+impl CapabilityContainer {
+    pub fn dev_type(&self) -> ndefDeviceType {
+        match self {
+            CapabilityContainer::T1t(_) => ndefDeviceType::NDEF_DEV_T1T,
+            CapabilityContainer::T2t(_) => ndefDeviceType::NDEF_DEV_T2T,
+            CapabilityContainer::T3t(_) => ndefDeviceType::NDEF_DEV_T3T,
+            CapabilityContainer::T4t(_) => ndefDeviceType::NDEF_DEV_T4T,
+            CapabilityContainer::T5t(_) => ndefDeviceType::NDEF_DEV_T5T,
+        }
+    }
+    /// Checks the container can be written to a tag.
+    pub fn validate(&self) -> Result<()> {
+        self.to_raw().map(|_| ())
+    }
+    pub(crate) fn from_raw(dev_type: ndefDeviceType, cc: &ndefCapabilityContainer) -> Result<Self> {
+        Ok(match dev_type {
+            ndefDeviceType::NDEF_DEV_T1T => {
+                CapabilityContainer::T1t(T1tCc::from_raw(unsafe { &cc.t1t }))
+            }
+            ndefDeviceType::NDEF_DEV_T2T => {
+                CapabilityContainer::T2t(T2tCc::from_raw(unsafe { &cc.t2t }))
+            }
+            ndefDeviceType::NDEF_DEV_T3T => {
+                CapabilityContainer::T3t(T3tAttributeInfo::from_raw(unsafe { &cc.t3t }))
+            }
+            ndefDeviceType::NDEF_DEV_T4T => {
+                CapabilityContainer::T4t(T4tCc::from_raw(unsafe { &cc.t4t }))
+            }
+            ndefDeviceType::NDEF_DEV_T5T => {
+                CapabilityContainer::T5t(T5tCc::from_raw(unsafe { &cc.t5t }))
+            }
+            _ => return Err(Error::Param),
+        })
+    }
+    pub(crate) fn to_raw(self) -> Result<ndefCapabilityContainer> {
+        Ok(match self {
+            CapabilityContainer::T1t(cc) => ndefCapabilityContainer { t1t: cc.to_raw()? },
+            CapabilityContainer::T2t(cc) => ndefCapabilityContainer { t2t: cc.to_raw()? },
+            CapabilityContainer::T3t(aib) => ndefCapabilityContainer { t3t: aib.to_raw()? },
+            CapabilityContainer::T4t(cc) => ndefCapabilityContainer { t4t: cc.to_raw()? },
+            CapabilityContainer::T5t(cc) => ndefCapabilityContainer { t5t: cc.to_raw()? },
+        })
+    }
+}
+
+/// Options of [`crate::Poller::tag_format`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Container to write, required except for T5T which computes it from the
+    /// system information when `None`.
+    pub cc: Option<CapabilityContainer>,
+    /// T5T only: compute MLEN the NFC Forum way instead of the Android one.
+    pub nfc_forum: bool,
+}
+
+impl FormatOptions {
+    pub fn new(cc: CapabilityContainer) -> Self {
+        Self {
+            cc: Some(cc),
+            nfc_forum: false,
+        }
+    }
+    pub(crate) fn options(&self) -> u32 {
+        // NDEF_T5T_FORMAT_OPTION_NFC_FORUM, ignored by the other tag types
+        self.nfc_forum as u32
+    }
+}
+
+/// State of the NDEF area of a tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NdefState {
+    /// No valid capability container.
+    Invalid,
+    /// Formatted, without NDEF message.
+    Initialized,
+    ReadWrite,
+    ReadOnly,
+}
+
+impl From<ndefState> for NdefState {
+    fn from(value: ndefState) -> Self {
+        match value {
+            ndefState::NDEF_STATE_INVALID => NdefState::Invalid,
+            ndefState::NDEF_STATE_INITIALIZED => NdefState::Initialized,
+            ndefState::NDEF_STATE_READWRITE => NdefState::ReadWrite,
+            ndefState::NDEF_STATE_READONLY => NdefState::ReadOnly,
+        }
+    }
+}
+
+/// NDEF information found by [`crate::Poller::ndef_detect`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NdefInfo {
+    pub version: Version,
+    pub area_len: u32,
+    /// Remaining space when a proprietary TLV is present.
+    pub available_space_len: u32,
+    pub message_len: u32,
+    pub state: NdefState,
+}
+
+impl From<ndefInfo> for NdefInfo {
+    fn from(value: ndefInfo) -> Self {
+        Self {
+            version: Version::new(value.majorVersion, value.minorVersion),
+            area_len: value.areaLen,
+            available_space_len: value.areaAvalableSpaceLen,
+            message_len: value.messageLen,
+            state: value.state.into(),
+        }
+    }
+}
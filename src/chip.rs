@@ -0,0 +1,113 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{result, Error, Result};
+
+/// Device ID string and ROM CRC returned by IDN.
+pub const ST25R95_IDN_LEN: usize = 15;
+
+// NUL terminated "NFC FS2JAST<rev>"
+const DEVICE_ID_LEN: usize = 13;
+
+/// IDN response of the ST25R95.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Idn {
+    raw: [u8; ST25R95_IDN_LEN],
+}
+
+impl Idn {
+    /// Device ID, "NFC FS2JAST4" for the ST25R95.
+    pub fn device_id(&self) -> &str {
+        let id = &self.raw[..DEVICE_ID_LEN];
+        let len = id.iter().position(|&b| b == 0).unwrap_or(id.len());
+        core::str::from_utf8(&id[..len]).unwrap_or_default()
+    }
+    /// ROM code revision, the last digit of the device ID.
+    pub fn firmware_version(&self) -> Option<u8> {
+        self.device_id()
+            .bytes()
+            .last()
+            .filter(u8::is_ascii_digit)
+            .map(|b| b - b'0')
+    }
+    pub fn rom_crc(&self) -> u16 {
+        u16::from_be_bytes([self.raw[DEVICE_ID_LEN], self.raw[DEVICE_ID_LEN + 1]])
+    }
+    pub fn raw(&self) -> &[u8; ST25R95_IDN_LEN] {
+        &self.raw
+    }
+}
+
+/// Analog registers of the ST25R95 reachable through RFAL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChipRegister {
+    /// Modulation index and receiver gain, ISO 14443-B, FeliCa and ISO 15693.
+    ArcB,
+    /// Load modulation index and demodulator sensitivity, card emulation.
+    AccA,
+}
+
+impl ChipRegister {
+    fn address(&self) -> u16 {
+        match self {
+            ChipRegister::ArcB => 0x6801,
+            ChipRegister::AccA => 0x6804,
+        }
+    }
+}
+
+/// Diagnostics of the reader chip, measurements the ST25R95 lacks return `NotSupp`.
+pub struct Chip;
+
+impl Chip {
+    pub fn idn() -> Result<Idn> {
+        let mut raw = [0u8; ST25R95_IDN_LEN];
+        let mut rcv_len = 0u8;
+        result(unsafe {
+            rfal_sys::rfalST25R95GetIdn(raw.as_mut_ptr(), raw.len() as u8, &mut rcv_len)
+        })?;
+        if rcv_len as usize != raw.len() {
+            return Err(Error::Proto);
+        }
+        Ok(Idn { raw })
+    }
+    /// Reads `reg`, a protocol must be selected with [`crate::Rf::set_mode`].
+    pub fn read_reg(reg: ChipRegister) -> Result<u8> {
+        let mut value = 0u8;
+        result(unsafe { rfal_sys::rfalChipReadReg(reg.address(), &mut value, 1) })?;
+        Ok(value)
+    }
+    /// Writes `reg` for the protocol currently selected.
+    pub fn write_reg(reg: ChipRegister, value: u8) -> Result<()> {
+        result(unsafe { rfal_sys::rfalChipWriteReg(reg.address(), &value, 1) })
+    }
+    /// Changes the bits of `reg` selected by `mask` to `value`.
+    pub fn change_reg_bits(reg: ChipRegister, mask: u8, value: u8) -> Result<()> {
+        result(unsafe { rfal_sys::rfalChipChangeRegBits(reg.address(), mask, value) })
+    }
+    pub fn measure_amplitude() -> Result<u8> {
+        let mut value = 0u8;
+        result(unsafe { rfal_sys::rfalChipMeasureAmplitude(&mut value) })?;
+        Ok(value)
+    }
+    pub fn measure_phase() -> Result<u8> {
+        let mut value = 0u8;
+        result(unsafe { rfal_sys::rfalChipMeasurePhase(&mut value) })?;
+        Ok(value)
+    }
+    pub fn measure_capacitance() -> Result<u8> {
+        let mut value = 0u8;
+        result(unsafe { rfal_sys::rfalChipMeasureCapacitance(&mut value) })?;
+        Ok(value)
+    }
+    /// Measures the supply selected by the chip specific `param`.
+    pub fn measure_power_supply(param: u8) -> Result<u8> {
+        let mut value = 0u8;
+        result(unsafe { rfal_sys::rfalChipMeasurePowerSupply(param, &mut value) })?;
+        Ok(value)
+    }
+    /// Runs the calibrations needed by the chip, none for the ST25R95.
+    pub fn calibrate() -> Result<()> {
+        result(unsafe { rfal_sys::rfalCalibrate() })
+    }
+}
@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::{result, rfalNfcDiscoverParam, Result};
+use crate::{result, rfalNfcDiscoverParam, rfalNfcbSlots, Result, WakeUpConfig};
 
 pub struct Discover {
     pub params: rfalNfcDiscoverParam,
@@ -56,12 +56,33 @@ impl Default for Discover {
                     reference: 0,
                 },
             },
+            nfcbInitSlots: rfalNfcbSlots::RFAL_NFCB_SLOT_NUM_1,
+            nfcbEndSlots: rfalNfcbSlots::RFAL_NFCB_SLOT_NUM_16,
         };
         Self { params }
     }
 }
 
 impl Discover {
+    /// Slots used by the NFC-B collision resolution, starting with `init` and
+    /// doubling up to `end` while collisions remain, see
+    /// [`crate::Nfc::nfcb_collision_pending`]. ISO compliance mode requires
+    /// `init` to be 1 slot.
+    pub fn nfcb_slots(&mut self, init: rfalNfcbSlots, end: rfalNfcbSlots) -> &mut Self {
+        self.params.nfcbInitSlots = init;
+        self.params.nfcbEndSlots = end;
+        self
+    }
+    /// Enters the wake-up mode before each discovery, with `config` or the RFAL
+    /// default one, `enabled` false polls continuously.
+    pub fn wakeup(&mut self, enabled: bool, config: Option<&WakeUpConfig>) -> Result<&mut Self> {
+        if let Some(config) = config {
+            self.params.wakeupConfig = config.to_raw()?;
+        }
+        self.params.wakeupEnabled = enabled;
+        self.params.wakeupConfigDefault = config.is_none();
+        Ok(self)
+    }
     pub fn start(&self) -> Result<()> {
         result(unsafe { rfal_sys::rfalNfcDiscover(&self.params as *const _) })
     }
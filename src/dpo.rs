@@ -0,0 +1,174 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+use crate::error::{RFAL_ERR_IO, RFAL_ERR_NONE};
+use crate::{result, Error, Result};
+use rfal_sys::rfalDpoEntry;
+
+/// Maximum number of power levels of the DPO table.
+pub const DPO_TABLE_MAX_ENTRIES: usize = rfal_sys::RFAL_DPO_TABLE_MAX_ENTRIES as usize;
+
+/// Measurement compared with the DPO table, see [`Dpo::set_measure`].
+pub type DpoMeasure = &'static mut (dyn FnMut() -> Result<u8> + Send);
+
+// measurement called by rfalDpoAdjust, set by Dpo::set_measure
+static MEASURE: Mutex<RefCell<Option<DpoMeasure>>> = Mutex::new(RefCell::new(None));
+
+/// Power level of the DPO table, the measurement moves one level up when it
+/// reaches `inc` and one level down when it falls to `dec`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DpoEntry {
+    /// RFO resistance, unused by the ST25R95.
+    pub rfo_res: u8,
+    pub inc: u8,
+    pub dec: u8,
+}
+
+impl From<rfalDpoEntry> for DpoEntry {
+    fn from(value: rfalDpoEntry) -> Self {
+        Self {
+            rfo_res: value.rfoRes,
+            inc: value.inc,
+            dec: value.dec,
+        }
+    }
+}
+
+impl From<DpoEntry> for rfalDpoEntry {
+    fn from(value: DpoEntry) -> Self {
+        Self {
+            rfoRes: value.rfo_res,
+            inc: value.inc,
+            dec: value.dec,
+        }
+    }
+}
+
+/// DPO table, from the highest output power to the lowest one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DpoTable {
+    entries: [DpoEntry; DPO_TABLE_MAX_ENTRIES],
+    len: usize,
+}
+
+impl DpoTable {
+    /// Checks there are 1 to [`DPO_TABLE_MAX_ENTRIES`] levels, each with `inc`
+    /// not below `dec`.
+    pub fn new(entries: &[DpoEntry]) -> Result<Self> {
+        if entries.is_empty() || entries.len() > DPO_TABLE_MAX_ENTRIES {
+            return Err(Error::Param);
+        }
+        if entries.iter().any(|e| e.inc < e.dec) {
+            return Err(Error::Param);
+        }
+        let mut table = Self {
+            len: entries.len(),
+            ..Default::default()
+        };
+        table.entries[..entries.len()].copy_from_slice(entries);
+        Ok(table)
+    }
+    pub fn entries(&self) -> &[DpoEntry] {
+        &self.entries[..self.len]
+    }
+}
+
+/// Dynamic Power Output, lowers the RF power when a device close to the
+/// antenna raises the measured amplitude. The ST25R95 cannot change its RFO
+/// resistance nor measure the amplitude on its own: each level applies the
+/// [`crate::AnalogDirection::Dpo`] analog configurations of its index, see
+/// [`crate::AnalogConfigId::with_dpo_level`], and the measurement must be
+/// provided with [`Dpo::set_measure`].
+pub struct Dpo;
+
+impl Dpo {
+    /// Disables DPO and loads the default table, to call once after
+    /// [`crate::Nfc::initialize`] as RFAL does not on the ST25R95.
+    pub fn initialize() {
+        unsafe { rfal_sys::rfalDpoInitialize() }
+    }
+    /// Enabling or disabling restarts from the highest power level.
+    pub fn set_enabled(enable: bool) {
+        unsafe { rfal_sys::rfalDpoSetEnabled(enable) }
+    }
+    pub fn is_enabled() -> bool {
+        unsafe { rfal_sys::rfalDpoIsEnabled() }
+    }
+    /// Sets the measurement compared with the table, a closure living for the
+    /// rest of the program, e.g. from a `static_cell::StaticCell` or `Box::leak`.
+    pub fn set_measure(measure: DpoMeasure) {
+        critical_section::with(|cs| MEASURE.borrow_ref_mut(cs).replace(measure));
+        unsafe { rfal_sys::rfalDpoSetMeasureCallback(Some(dpo_measure)) }
+    }
+    pub fn write_table(table: &DpoTable) -> Result<()> {
+        // allocate default values manually, thanks bingen to not deriving Default trait...
+        let mut raw = [rfalDpoEntry {
+            rfoRes: 0,
+            inc: 0,
+            dec: 0,
+        }; DPO_TABLE_MAX_ENTRIES];
+        for (raw, entry) in raw.iter_mut().zip(table.entries()) {
+            *raw = (*entry).into();
+        }
+        result(unsafe { rfal_sys::rfalDpoTableWrite(raw.as_ptr(), table.len as u8) })
+    }
+    pub fn read_table() -> Result<DpoTable> {
+        // allocate default values manually, thanks bingen to not deriving Default trait...
+        let mut raw = [rfalDpoEntry {
+            rfoRes: 0,
+            inc: 0,
+            dec: 0,
+        }; DPO_TABLE_MAX_ENTRIES];
+        let mut len = 0u8;
+        result(unsafe { rfal_sys::rfalDpoTableRead(raw.as_mut_ptr(), raw.len() as u8, &mut len) })?;
+        let mut table = DpoTable {
+            len: (len as usize).min(DPO_TABLE_MAX_ENTRIES),
+            ..Default::default()
+        };
+        for (entry, raw) in table.entries.iter_mut().zip(raw) {
+            *entry = raw.into();
+        }
+        Ok(table)
+    }
+    /// Measures and moves to the power level matching it, to call in poll mode
+    /// before each exchange.
+    pub fn adjust() -> Result<()> {
+        result(unsafe { rfal_sys::rfalDpoAdjust() })
+    }
+    /// Index in the table of the current power level, 0 being the highest.
+    pub fn current_index() -> u8 {
+        unsafe { rfal_sys::rfalDpoGetCurrentTableIndex() }
+    }
+    pub fn current_entry() -> Result<DpoEntry> {
+        let table = Self::read_table()?;
+        table
+            .entries()
+            .get(Self::current_index() as usize)
+            .copied()
+            .ok_or(Error::WrongState)
+    }
+}
+
+unsafe extern "C" fn dpo_measure(res: *mut u8) -> u16 {
+    // measures outside of the critical section, the closure is put back after
+    let mut measure = critical_section::with(|cs| MEASURE.borrow_ref_mut(cs).take());
+    let value = measure.as_mut().map(|measure| measure());
+    critical_section::with(|cs| {
+        let mut current = MEASURE.borrow_ref_mut(cs);
+        // unless replaced in the meantime
+        if current.is_none() {
+            *current = measure;
+        }
+    });
+    match value {
+        Some(Ok(value)) => {
+            *res = value;
+            RFAL_ERR_NONE
+        }
+        _ => RFAL_ERR_IO,
+    }
+}
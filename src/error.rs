@@ -1,7 +1,9 @@
 // SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-#[derive(Debug, PartialEq)]
+use core::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Error {
     NotInitialized,
 
@@ -43,14 +45,30 @@ pub enum Error {
     HwMismatch,
     LinkLoss,
     InvalidHandle,
-    IncompleteByte,
+    /// Incomplete last byte received, with its number of bits. The ST25R95
+    /// driver only returns them in the received length, so the bits are 0
+    /// unless the error comes from [`crate::Rf::transceive`].
+    IncompleteByte(u8),
     Unknown(u16),
 }
 
-const RFAL_ERR_NONE: u16 = 0; // no error occurred
+/// Broad category of an [`Error`], to decide how to handle it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Disturbed or missed RF exchange, trying again may succeed.
+    Transient,
+    /// Unexpected answer or behaviour of the remote device.
+    Protocol,
+    /// Failure of the reader chip or of its communication.
+    Hardware,
+    /// Wrong parameter, state or unsupported request from the caller.
+    Usage,
+}
+
+pub(crate) const RFAL_ERR_NONE: u16 = 0; // no error occurred
 const RFAL_ERR_NOMEM: u16 = 1; // not enough memory to perform the requested operation
 const RFAL_ERR_BUSY: u16 = 2; // device or resource busy
-const RFAL_ERR_IO: u16 = 3; // generic IO error
+pub(crate) const RFAL_ERR_IO: u16 = 3; // generic IO error
 const RFAL_ERR_TIMEOUT: u16 = 4; // error due to timeout
 const RFAL_ERR_REQUEST: u16 = 5; // invalid request or requested function can't be executed at the moment
 const RFAL_ERR_NOMSG: u16 = 6; // No message of desired type
@@ -88,13 +106,8 @@ const RFAL_ERR_LINK_LOSS: u16 = 37; // Other device's field didn't behave as exp
 const RFAL_ERR_INVALID_HANDLE: u16 = 38; // invalid or not initialized device handle
 const RFAL_ERR_INCOMPLETE_BYTE: u16 = 40; // Incomplete byte rcvd
 
-// const RFAL_ERR_INCOMPLETE_BYTE_01: u16 = 41; // Incomplete byte rcvd - 1 bit
-// const RFAL_ERR_INCOMPLETE_BYTE_02: u16 = 42; // Incomplete byte rcvd - 2 bit
-// const RFAL_ERR_INCOMPLETE_BYTE_03: u16 = 43; // Incomplete byte rcvd - 3 bit
-// const RFAL_ERR_INCOMPLETE_BYTE_04: u16 = 44; // Incomplete byte rcvd - 4 bit
-// const RFAL_ERR_INCOMPLETE_BYTE_05: u16 = 45; // Incomplete byte rcvd - 5 bit
-// const RFAL_ERR_INCOMPLETE_BYTE_06: u16 = 46; // Incomplete byte rcvd - 6 bit
-// const RFAL_ERR_INCOMPLETE_BYTE_07: u16 = 47; // Incomplete byte rcvd - 7 bit
+// 41 to 47: Incomplete byte rcvd - 1 to 7 bit
+const RFAL_ERR_INCOMPLETE_BYTE_07: u16 = 47; // Incomplete byte rcvd - 7 bit
 
 impl From<u16> for Error {
     fn from(value: u16) -> Self {
@@ -137,12 +150,160 @@ impl From<u16> for Error {
             RFAL_ERR_HW_MISMATCH => Error::HwMismatch,
             RFAL_ERR_LINK_LOSS => Error::LinkLoss,
             RFAL_ERR_INVALID_HANDLE => Error::InvalidHandle,
-            RFAL_ERR_INCOMPLETE_BYTE => Error::IncompleteByte,
+            v @ RFAL_ERR_INCOMPLETE_BYTE..=RFAL_ERR_INCOMPLETE_BYTE_07 => {
+                Error::IncompleteByte((v - RFAL_ERR_INCOMPLETE_BYTE) as u8)
+            }
             v => Error::Unknown(v),
         }
     }
 }
 
+impl Error {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Timeout
+            | Error::Crc
+            | Error::Framing
+            | Error::Par
+            | Error::RfCollision
+            | Error::IncompleteByte(_)
+            | Error::Overrun
+            | Error::HwOverrun
+            | Error::Fifo
+            | Error::Busy
+            | Error::Again
+            | Error::LinkLoss => ErrorKind::Transient,
+            Error::NoMsg
+            | Error::Proto
+            | Error::Send
+            | Error::Ignore
+            | Error::Semantic
+            | Error::Syntax
+            | Error::NotFound
+            | Error::NotUnique
+            | Error::Write
+            | Error::Done
+            | Error::ReleaseReq
+            | Error::SleepReq
+            | Error::MaxReruns => ErrorKind::Protocol,
+            Error::Io
+            | Error::System
+            | Error::Internal
+            | Error::MemCorrupt
+            | Error::PcCorrupt
+            | Error::HwMismatch
+            | Error::Unknown(_) => ErrorKind::Hardware,
+            Error::NotInitialized
+            | Error::NoMem
+            | Error::Request
+            | Error::Param
+            | Error::NotImplemented
+            | Error::NotSupp
+            | Error::WrongState
+            | Error::Disabled
+            | Error::InvalidHandle => ErrorKind::Usage,
+        }
+    }
+    pub fn is_timeout(&self) -> bool {
+        *self == Error::Timeout
+    }
+    /// Whether the same operation may succeed when tried again.
+    pub fn is_retryable(&self) -> bool {
+        self.kind() == ErrorKind::Transient
+    }
+    /// Adds the operation that failed, for logs.
+    pub fn context(self, op: &'static str) -> ContextError {
+        ContextError { op, error: self }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Error::NotInitialized => "not initialized",
+            Error::NoMem => "not enough memory",
+            Error::Busy => "device or resource busy",
+            Error::Io => "generic IO error",
+            Error::Timeout => "timeout",
+            Error::Request => "invalid request",
+            Error::NoMsg => "no message of desired type",
+            Error::Param => "parameter error",
+            Error::System => "system error",
+            Error::Framing => "framing error",
+            Error::Overrun => "lost one or more received bytes",
+            Error::Proto => "protocol error",
+            Error::Internal => "internal error",
+            Error::Again => "call again",
+            Error::MemCorrupt => "memory corruption",
+            Error::NotImplemented => "not implemented",
+            Error::PcCorrupt => "program counter corruption",
+            Error::Send => "error sending",
+            Error::Ignore => "error to be ignored",
+            Error::Semantic => "unexpected command",
+            Error::Syntax => "unknown command",
+            Error::Crc => "CRC error",
+            Error::NotFound => "transponder not found",
+            Error::NotUnique => "transponder not unique",
+            Error::NotSupp => "operation not supported",
+            Error::Write => "write error",
+            Error::Fifo => "FIFO over or underflow",
+            Error::Par => "parity error",
+            Error::Done => "transfer already finished",
+            Error::RfCollision => "RF collision",
+            Error::HwOverrun => "hardware overrun",
+            Error::ReleaseReq => "device requested release",
+            Error::SleepReq => "device requested sleep",
+            Error::WrongState => "wrong state",
+            Error::MaxReruns => "maximum reruns reached",
+            Error::Disabled => "disabled by configuration",
+            Error::HwMismatch => "hardware mismatch",
+            Error::LinkLoss => "link loss",
+            Error::InvalidHandle => "invalid device handle",
+            Error::IncompleteByte(bits) => return write!(f, "incomplete byte, {bits} bits"),
+            Error::Unknown(code) => return write!(f, "unknown error {code}"),
+        };
+        f.write_str(msg)
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// [`Error`] with the operation that failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContextError {
+    pub op: &'static str,
+    pub error: Error,
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.op, self.error)
+    }
+}
+
+impl core::error::Error for ContextError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<ContextError> for Error {
+    fn from(value: ContextError) -> Self {
+        value.error
+    }
+}
+
+/// Adds the operation that failed to a [`Result`].
+pub trait ResultExt<T> {
+    fn context(self, op: &'static str) -> core::result::Result<T, ContextError>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn context(self, op: &'static str) -> core::result::Result<T, ContextError> {
+        self.map_err(|e| e.context(op))
+    }
+}
+
 pub type Result<T> = core::result::Result<T, Error>;
 
 pub(crate) fn result(res: u16) -> Result<()> {
@@ -0,0 +1,174 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(not(feature = "alloc"))]
+use heapless::Vec;
+
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::ticks::{deadline, expired, now};
+use crate::{nfc::Device, result, rfalNfcState, Discover, Error, Nfc, Result};
+use rfal_sys::rfalNfcDevice;
+
+/// Maximum number of devices RFAL keeps from one discovery.
+// RFAL_NFC_MAX_DEVICES is defined in rfal_nfc.c
+pub const NFC_MAX_DEVICES: usize = 5;
+
+/// Discovers up to `N` devices and works with each of them in turn, the
+/// devices are activated one at a time and put to sleep once done.
+pub struct Inventory<const N: usize = NFC_MAX_DEVICES> {
+    /// Time given to the activation of a device and to its deactivation.
+    pub timeout_ms: u32,
+    count: u8,
+}
+
+impl<const N: usize> Default for Inventory<N> {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 500,
+            count: 0,
+        }
+    }
+}
+
+impl<const N: usize> Inventory<N> {
+    /// Starts a discovery of up to `N` devices, RFAL waits for a selection
+    /// when several are found instead of activating the first one.
+    pub fn start(&mut self, discover: &mut Discover) -> Result<()> {
+        if N == 0 || N > NFC_MAX_DEVICES {
+            return Err(Error::Param);
+        }
+        discover.params.devLimit = N as u8;
+        // RFAL only stops for a selection when it can notify it, the callback
+        // already set is still notified
+        let caller = discover.params.notifyCb.replace(notify);
+        if caller.is_none_or(|cb| cb as *const () != notify as *const ()) {
+            let caller = caller.map_or(core::ptr::null_mut(), |cb| cb as *mut ());
+            CALLER_NOTIFY.store(caller, Ordering::Relaxed);
+        }
+        self.count = 0;
+        discover.start()
+    }
+    /// Runs the discovery, returns the number of devices once found. A single
+    /// device is activated right away.
+    pub fn poll(&mut self, nfc: &Nfc) -> Result<Option<usize>> {
+        nfc.worker();
+        if !matches!(
+            nfc.state(),
+            rfalNfcState::RFAL_NFC_STATE_POLL_SELECT | rfalNfcState::RFAL_NFC_STATE_ACTIVATED
+        ) {
+            return Ok(None);
+        }
+        let mut dev_list: *mut rfalNfcDevice = core::ptr::null_mut();
+        result(unsafe { rfal_sys::rfalNfcGetDevicesFound(&mut dev_list, &mut self.count) })?;
+        Ok(Some(self.count as usize))
+    }
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+    /// Activates the device `dev_idx` of the list.
+    pub fn select(&mut self, nfc: &Nfc, dev_idx: u8) -> Result<Device> {
+        if dev_idx >= self.count {
+            return Err(Error::Param);
+        }
+        // the only device found is already active
+        if self.count == 1 && nfc.state() == rfalNfcState::RFAL_NFC_STATE_ACTIVATED {
+            return nfc.active_device();
+        }
+        nfc.select(dev_idx)?;
+        let state = self.wait(nfc)?;
+        // RFAL goes back to the selection when the activation fails
+        if state != rfalNfcState::RFAL_NFC_STATE_ACTIVATED {
+            return Err(Error::NotFound);
+        }
+        nfc.active_device()
+    }
+    /// Puts the active device to sleep, to select the next one.
+    pub fn release(&mut self, nfc: &Nfc) -> Result<()> {
+        nfc.deactivate_and_sleep()?;
+        match self.wait(nfc)? {
+            rfalNfcState::RFAL_NFC_STATE_POLL_SELECT => Ok(()),
+            _ => Err(Error::WrongState),
+        }
+    }
+    /// Runs `op` on each device found, activating it before and putting it to
+    /// sleep after, and returns the result of each. Fails if the selection
+    /// itself breaks, the reader is then left to the caller to deactivate.
+    #[cfg(feature = "alloc")]
+    pub fn for_each<R>(
+        &mut self,
+        nfc: &Nfc,
+        mut op: impl FnMut(&Nfc, &Device) -> Result<R>,
+    ) -> Result<Vec<Result<R>>> {
+        let mut results = Vec::new();
+        for dev_idx in 0..self.count {
+            results.push(self.visit(nfc, dev_idx, &mut op)?);
+        }
+        Ok(results)
+    }
+    /// Runs `op` on each device found, activating it before and putting it to
+    /// sleep after, and returns the result of each. Fails if the selection
+    /// itself breaks, the reader is then left to the caller to deactivate.
+    #[cfg(not(feature = "alloc"))]
+    pub fn for_each<R>(
+        &mut self,
+        nfc: &Nfc,
+        mut op: impl FnMut(&Nfc, &Device) -> Result<R>,
+    ) -> Result<Vec<Result<R>, N>> {
+        let mut results = Vec::new();
+        for dev_idx in 0..self.count {
+            let result = self.visit(nfc, dev_idx, &mut op)?;
+            results.push(result).map_err(|_| Error::NoMem)?;
+        }
+        Ok(results)
+    }
+    fn visit<R>(
+        &mut self,
+        nfc: &Nfc,
+        dev_idx: u8,
+        op: &mut impl FnMut(&Nfc, &Device) -> Result<R>,
+    ) -> Result<Result<R>> {
+        let dev = match self.select(nfc, dev_idx) {
+            Ok(dev) => dev,
+            // skipped, the others can still be selected
+            Err(Error::NotFound) => return Ok(Err(Error::NotFound)),
+            Err(err) => return Err(err),
+        };
+        let result = op(nfc, &dev);
+        self.release(nfc)?;
+        Ok(result)
+    }
+    // runs the worker until RFAL waits for the caller again
+    fn wait(&self, nfc: &Nfc) -> Result<rfalNfcState> {
+        let deadline = deadline(now(), self.timeout_ms);
+        loop {
+            nfc.worker();
+            let state = nfc.state();
+            if matches!(
+                state,
+                rfalNfcState::RFAL_NFC_STATE_POLL_SELECT | rfalNfcState::RFAL_NFC_STATE_ACTIVATED
+            ) {
+                return Ok(state);
+            }
+            if expired(now(), deadline) {
+                return Err(Error::Timeout);
+            }
+        }
+    }
+}
+
+// callback set by the caller before Inventory::start replaced it, null if none
+static CALLER_NOTIFY: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+unsafe extern "C" fn notify(state: rfalNfcState) {
+    let cb = CALLER_NOTIFY.load(Ordering::Relaxed);
+    if !cb.is_null() {
+        let cb: unsafe extern "C" fn(rfalNfcState) = core::mem::transmute(cb);
+        cb(state);
+    }
+}
@@ -1,26 +1,85 @@
 // SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+//! Rust bindings for STMicroelectronics RFAL and NDEF middlewares.
+//!
+//! The `dpo` feature adds `Dpo`, which keeps its measurement closure behind
+//! a `critical-section` mutex: the final binary must then provide a
+//! `critical-section` implementation, usually from its HAL or runtime crate.
+
 #![no_std]
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+mod analog;
+mod capability;
+mod chip;
 mod discover;
+#[cfg(feature = "dpo")]
+mod dpo;
 mod error;
+mod inventory;
+mod media;
 mod ndef;
 mod nfc;
+mod nfca;
+mod nfcb;
+mod nfcf;
+mod nfcv;
+mod ntag;
+mod power;
+mod presence;
+mod record;
+mod retry;
+mod rf;
+mod rtd;
+mod st25dv;
+mod st25tb;
+mod t1t;
+mod t2t;
+mod ticks;
+mod tnep;
+mod wakeup;
+mod wlc;
 
+pub use analog::*;
+pub use capability::*;
+pub use chip::*;
 pub use discover::*;
+#[cfg(feature = "dpo")]
+pub use dpo::*;
 pub use error::*;
+pub use inventory::*;
+pub use media::*;
 pub use ndef::*;
 pub use nfc::*;
+pub use nfca::*;
+pub use nfcb::*;
+pub use nfcf::*;
+pub use nfcv::*;
+pub use ntag::*;
+pub use power::*;
+pub use presence::*;
+pub use record::*;
+pub use retry::*;
+pub use rf::*;
 pub use rfal_sys::{
     ndefCapabilityContainer, ndefCapabilityContainerT1T, ndefCapabilityContainerT2T,
-    ndefDeviceType, ndefInfo, ndefState, rfalBitRate, rfalComplianceMode, rfalLmNfcidLen,
-    rfalNfcDevType, rfalNfcDiscoverParam, rfalNfcState, rfalNfcaListenDevice, Platform,
-    RFAL_FWT_NONE, RFAL_NFC_LISTEN_TECH_A, RFAL_NFC_POLL_TECH_A, RFAL_NFC_TECH_NONE,
+    ndefDeviceType, ndefInfo, ndefState, rfal14443AShortFrameCmd, rfalBitRate, rfalComplianceMode,
+    rfalFeliCaPollSlots, rfalLmNfcidLen, rfalNfcDevType, rfalNfcDiscoverParam, rfalNfcState,
+    rfalNfcaListenDevice, rfalNfcbSensCmd, rfalNfcbSlots, rfalWumPeriod, Platform, RFAL_FWT_NONE,
+    RFAL_NFC_LISTEN_TECH_A, RFAL_NFC_POLL_TECH_A, RFAL_NFC_POLL_TECH_B, RFAL_NFC_POLL_TECH_F,
+    RFAL_NFC_POLL_TECH_ST25TB, RFAL_NFC_POLL_TECH_V, RFAL_NFC_TECH_NONE,
 };
+pub use rtd::*;
+pub use st25dv::*;
+pub use st25tb::*;
+pub use t1t::*;
+pub use t2t::*;
+pub use tnep::*;
+pub use wakeup::*;
+pub use wlc::*;
 
 pub struct Rfal {
     pub discover: Discover,
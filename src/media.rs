@@ -0,0 +1,170 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{
+    record::{const_buffer, const_buffer8, empty_record, empty_type, encode_type, slice},
+    result, Encode, Error, Result, Tnf,
+};
+use rfal_sys::{ndefConstBuffer, ndefType, ndefTypeVCard};
+
+/// MIME media record, e.g. `image/png` or `application/json`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Media<'a> {
+    pub mime_type: &'a [u8],
+    pub payload: &'a [u8],
+}
+
+impl<'a> Media<'a> {
+    pub fn new(mime_type: &'a [u8], payload: &'a [u8]) -> Result<Self> {
+        if mime_type.is_empty() || mime_type.len() > u8::MAX as usize {
+            return Err(Error::Param);
+        }
+        Ok(Self { mime_type, payload })
+    }
+}
+
+impl Encode for Media<'_> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        let mut ty = empty_type();
+        result(unsafe {
+            rfal_sys::ndefMediaInit(
+                &mut ty,
+                &const_buffer8(self.mime_type),
+                &const_buffer(self.payload),
+            )
+        })?;
+        encode_type(&ty, buf)
+    }
+}
+
+/// A single vCard content line, e.g. `TEL;TYPE=CELL:+1234` is made of
+/// name `TEL`, subtype `TYPE=CELL` and value `+1234`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VCardProperty<'a> {
+    pub name: &'a [u8],
+    pub subtype: Option<&'a [u8]>,
+    pub value: &'a [u8],
+}
+
+impl<'a> VCardProperty<'a> {
+    pub fn parse(line: &'a [u8]) -> Result<Self> {
+        let mut name = ndefConstBuffer {
+            buffer: core::ptr::null(),
+            length: 0,
+        };
+        let mut subtype = name;
+        let mut value = name;
+        result(unsafe {
+            rfal_sys::ndefVCardParseProperty(
+                &const_buffer(line),
+                &mut name,
+                &mut subtype,
+                &mut value,
+            )
+        })?;
+        Ok(Self {
+            name: unsafe { slice(name.buffer, name.length as usize) },
+            subtype: (subtype.length != 0)
+                .then(|| unsafe { slice(subtype.buffer, subtype.length as usize) }),
+            value: unsafe { slice(value.buffer, value.length as usize) },
+        })
+    }
+}
+
+/// vCard media record (`text/x-vCard`), holding up to
+/// `NDEF_VCARD_PROPERTY_COUNT` content lines borrowed from a buffer.
+#[derive(Clone, Copy)]
+pub struct VCard<'a> {
+    raw: ndefTypeVCard,
+    _buf: core::marker::PhantomData<&'a [u8]>,
+}
+
+impl<'a> VCard<'a> {
+    /// Empty vCard, `BEGIN`, `VERSION` and `END` lines must be set before encoding.
+    pub fn new() -> Self {
+        let mut raw = ndefTypeVCard {
+            propertyBuffer: [core::ptr::null(); rfal_sys::NDEF_VCARD_PROPERTY_COUNT as usize],
+            propertyLength: [0; rfal_sys::NDEF_VCARD_PROPERTY_COUNT as usize],
+        };
+        unsafe { rfal_sys::ndefVCardReset(&mut raw) };
+        Self {
+            raw,
+            _buf: core::marker::PhantomData,
+        }
+    }
+    pub fn decode(payload: &'a [u8]) -> Result<Self> {
+        // phones write either `text/vcard` or `text/x-vcard`, while ST only matches
+        // its own `text/x-vCard` spelling: decode through a record of that type
+        let mut record = empty_record();
+        result(unsafe {
+            rfal_sys::ndefRecordInit(
+                &mut record,
+                Tnf::Media.into(),
+                &rfal_sys::bufMediaTypeVCard,
+                &const_buffer8(&[]),
+                &const_buffer(payload),
+            )
+        })?;
+        let mut ty = empty_type();
+        result(unsafe { rfal_sys::ndefRecordToVCard(&record, &mut ty) })?;
+        let mut vcard = Self::new();
+        result(unsafe { rfal_sys::ndefGetVCard(&ty, &mut vcard.raw) })?;
+        Ok(vcard)
+    }
+    pub(crate) fn is_vcard(mime_type: &[u8]) -> bool {
+        mime_type.eq_ignore_ascii_case(b"text/x-vcard")
+            || mime_type.eq_ignore_ascii_case(b"text/vcard")
+    }
+    /// Adds a content line (including its trailing "\r\n"),
+    /// replacing any existing line with the same property name.
+    pub fn set_property(&mut self, line: &'a [u8]) -> Result<()> {
+        if line.len() > u16::MAX as usize {
+            return Err(Error::Param);
+        }
+        result(unsafe { rfal_sys::ndefVCardSetProperty(&mut self.raw, &const_buffer(line)) })
+    }
+    pub fn property(&self, name: &[u8]) -> Option<VCardProperty<'a>> {
+        let mut line = ndefConstBuffer {
+            buffer: core::ptr::null(),
+            length: 0,
+        };
+        result(unsafe {
+            rfal_sys::ndefVCardGetProperty(&self.raw, &const_buffer(name), &mut line)
+        })
+        .ok()?;
+        VCardProperty::parse(unsafe { slice(line.buffer, line.length as usize) }).ok()
+    }
+    pub fn properties(&self) -> impl Iterator<Item = VCardProperty<'a>> + '_ {
+        self.raw
+            .propertyBuffer
+            .iter()
+            .zip(self.raw.propertyLength.iter())
+            .filter(|(buf, _)| !buf.is_null())
+            .filter_map(|(&buf, &len)| {
+                VCardProperty::parse(unsafe { slice(buf, len as usize) }).ok()
+            })
+    }
+    fn to_type(self) -> Result<ndefType> {
+        let mut ty = empty_type();
+        result(unsafe { rfal_sys::ndefVCardInit(&mut ty, &self.raw) })?;
+        Ok(ty)
+    }
+}
+
+impl Default for VCard<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Encode for VCard<'_> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        encode_type(&self.to_type()?, buf)
+    }
+}
+
+impl core::fmt::Debug for VCard<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.properties()).finish()
+    }
+}
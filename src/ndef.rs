@@ -1,9 +1,14 @@
 // SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(not(feature = "alloc"))]
+use heapless::Vec;
+
 use crate::{
-    ndefCapabilityContainer, ndefDeviceType, ndefInfo, ndefState, nfc::Device, result, Error,
-    Result,
+    ndefDeviceType, ndefInfo, ndefState, nfc::Device, result, CapabilityContainer, Error,
+    FormatOptions, NdefInfo, NdefState, Nfc, Result, Retry, RetryPolicy, RetryStats,
 };
 
 #[derive(Default)]
@@ -14,12 +19,13 @@ pub struct Ndef {
 #[derive(Default)]
 pub struct Poller {
     ctx: Option<rfal_sys::ndefContext>,
+    retry: Option<Retry>,
 }
 
 impl Poller {
     pub fn initialize(&mut self, nfc_dev: &Device) -> Result<()> {
         // allocate default values manually, thanks bingen to not deriving Default trait...
-        let mut ndef_ctx = rfal_sys::ndefContext {
+        let ndef_ctx = rfal_sys::ndefContext {
             type_: ndefDeviceType::NDEF_DEV_NONE,
             device: nfc_dev.0,
             state: ndefState::NDEF_STATE_INVALID,
@@ -44,77 +50,319 @@ impl Poller {
                 },
             },
         };
-        result(unsafe { rfal_sys::ndefPollerContextInitialization(&mut ndef_ctx, &nfc_dev.0) })?;
-        self.ctx.replace(ndef_ctx);
-        Ok(())
-    }
-    pub fn ndef_detect(&mut self) -> Result<ndefInfo> {
-        match self.ctx {
-            Some(mut ctx) => {
-                // allocate default values manually, thanks bingen to not deriving Default trait...
-                let mut ndef_info = ndefInfo {
-                    majorVersion: 0,
-                    minorVersion: 0,
-                    areaLen: 0,
-                    areaAvalableSpaceLen: 0,
-                    messageLen: 0,
-                    state: ndefState::NDEF_STATE_INVALID,
-                };
-                result(unsafe { rfal_sys::ndefPollerNdefDetect(&mut ctx, &mut ndef_info) })?;
-                self.ctx.replace(ctx);
-                Ok(ndef_info)
-            }
-            None => Err(Error::NotInitialized),
+        let ctx = self.ctx.insert(ndef_ctx);
+        if let Err(err) =
+            result(unsafe { rfal_sys::ndefPollerContextInitialization(ctx, &nfc_dev.0) })
+        {
+            self.ctx = None;
+            return Err(err);
         }
+        self.ctx_mut().map(|_| ())
+    }
+    pub fn ndef_detect(&mut self) -> Result<NdefInfo> {
+        self.retried(|poller| {
+            let ctx = poller.ctx_mut()?;
+            // allocate default values manually, thanks bingen to not deriving Default trait...
+            let mut ndef_info = ndefInfo {
+                majorVersion: 0,
+                minorVersion: 0,
+                areaLen: 0,
+                areaAvalableSpaceLen: 0,
+                messageLen: 0,
+                state: ndefState::NDEF_STATE_INVALID,
+            };
+            result(unsafe { rfal_sys::ndefPollerNdefDetect(ctx, &mut ndef_info) })?;
+            Ok(ndef_info.into())
+        })
     }
     pub fn ndef_ctx_type(&self) -> Option<ndefDeviceType> {
         self.ctx.as_ref().map(|ctx| ctx.type_)
     }
-    pub fn ndef_ctx_state(&self) -> Option<ndefState> {
-        self.ctx.as_ref().map(|ctx| ctx.state)
-    }
-    pub fn read_raw_message(&mut self) -> Result<&[u8]> {
-        match self.ctx {
-            Some(mut ctx) => {
-                let mut raw_message_buf = [0u8; 256];
-                let mut received_len = 0u32;
-                result(unsafe {
-                    rfal_sys::ndefPollerReadRawMessage(
-                        &mut ctx,
-                        raw_message_buf.as_mut_ptr() as *mut _,
-                        raw_message_buf.len() as u32,
-                        &mut received_len,
-                        true,
-                    )
-                })?;
-                self.ctx.replace(ctx);
-                Ok(unsafe {
-                    core::slice::from_raw_parts(raw_message_buf.as_ptr(), received_len as usize)
-                })
-            }
+    pub fn ndef_ctx_state(&self) -> Option<NdefState> {
+        self.ctx.as_ref().map(|ctx| ctx.state.into())
+    }
+    /// Capability container read by the last NDEF detection.
+    pub fn capability_container(&self) -> Result<CapabilityContainer> {
+        match &self.ctx {
+            Some(ctx) => CapabilityContainer::from_raw(ctx.type_, &ctx.cc),
             None => Err(Error::NotInitialized),
         }
     }
+    /// Reads an NDEF message of up to 256 bytes, see [`Poller::read_raw_message_into`]
+    /// for longer ones.
+    #[cfg(feature = "alloc")]
+    pub fn read_raw_message(&mut self) -> Result<Vec<u8>> {
+        let mut raw_message_buf = [0u8; 256];
+        let received_len = self.read_raw_message_into(&mut raw_message_buf, true)?;
+        Ok(raw_message_buf[..received_len].to_vec())
+    }
+    /// Reads an NDEF message of up to 256 bytes, see [`Poller::read_raw_message_into`]
+    /// for longer ones.
+    #[cfg(not(feature = "alloc"))]
+    pub fn read_raw_message(&mut self) -> Result<Vec<u8, 256>> {
+        let mut raw_message_buf = [0u8; 256];
+        let received_len = self.read_raw_message_into(&mut raw_message_buf, true)?;
+        Vec::from_slice(&raw_message_buf[..received_len]).map_err(|_| Error::NoMem)
+    }
+    /// Reads the NDEF message into `buf` and returns its length, `single` skips
+    /// reading the NDEF length again when it has just been detected.
+    pub fn read_raw_message_into(&mut self, buf: &mut [u8], single: bool) -> Result<usize> {
+        self.retried(|poller| {
+            let ctx = poller.ctx_mut()?;
+            let mut received_len = 0u32;
+            result(unsafe {
+                rfal_sys::ndefPollerReadRawMessage(
+                    ctx,
+                    buf.as_mut_ptr(),
+                    buf.len() as u32,
+                    &mut received_len,
+                    single,
+                )
+            })?;
+            Ok(received_len as usize)
+        })
+    }
     pub fn write_raw_message(&mut self, msg: &[u8]) -> Result<()> {
-        match self.ctx {
-            Some(mut ctx) => {
-                result(unsafe {
-                    rfal_sys::ndefPollerWriteRawMessage(&mut ctx, msg.as_ptr(), msg.len() as u32)
-                })?;
-                self.ctx.replace(ctx);
-                Ok(())
-            }
+        self.retried(|poller| {
+            let ctx = poller.ctx_mut()?;
+            result(unsafe {
+                rfal_sys::ndefPollerWriteRawMessage(ctx, msg.as_ptr(), msg.len() as u32)
+            })?;
+            Ok(())
+        })
+    }
+    /// Checks the tag is still in the field, usually failing with `Error::Timeout`
+    /// once removed, never tried again as a removal is what it reports.
+    pub fn check_presence(&mut self) -> Result<()> {
+        result(unsafe { rfal_sys::ndefPollerCheckPresence(self.ctx_mut()?) })
+    }
+    /// Checks a message of `len` bytes fits in the NDEF area, fails with `Error::NoMem` otherwise.
+    pub fn check_available_space(&self, len: u32) -> Result<()> {
+        // only compares lengths, the T5T UID is not dereferenced
+        match &self.ctx {
+            Some(ctx) => result(unsafe { rfal_sys::ndefPollerCheckAvailableSpace(ctx, len) }),
             None => Err(Error::NotInitialized),
         }
     }
-    pub fn tag_format(&mut self, cc: ndefCapabilityContainer, option: u32) -> Result<()> {
-        match self.ctx {
-            Some(mut ctx) => {
-                result(unsafe { rfal_sys::ndefPollerTagFormat(&mut ctx, &cc, option) })?;
-                self.ctx.replace(ctx);
-                Ok(())
+    /// Permanently locks the tag, there is no way back.
+    pub fn set_read_only(&mut self) -> Result<()> {
+        self.retried(|poller| {
+            let ctx = poller.ctx_mut()?;
+            result(unsafe { rfal_sys::ndefPollerSetReadOnly(ctx) })?;
+            Ok(())
+        })
+    }
+    /// Reads `buf.len()` bytes of the NDEF area from `offset` and returns the number of bytes read.
+    pub fn read_bytes(&mut self, offset: u32, buf: &mut [u8]) -> Result<usize> {
+        self.retried(|poller| {
+            let ctx = poller.ctx_mut()?;
+            let mut received_len = 0u32;
+            result(unsafe {
+                rfal_sys::ndefPollerReadBytes(
+                    ctx,
+                    offset,
+                    buf.len() as u32,
+                    buf.as_mut_ptr(),
+                    &mut received_len,
+                )
+            })?;
+            Ok(received_len as usize)
+        })
+    }
+    /// Writes `data` in the NDEF area at `offset`, `pad` fills the end of the
+    /// last block with zeroes instead of preserving its content.
+    pub fn write_bytes(&mut self, offset: u32, data: &[u8], pad: bool) -> Result<()> {
+        self.retried(|poller| {
+            let ctx = poller.ctx_mut()?;
+            // ndefPollerWriteBytes() never pads, go through the wrapper to reach the flag
+            let write_bytes = unsafe { ctx.ndefPollWrapper.as_ref() }
+                .ok_or(Error::WrongState)?
+                .pollerWriteBytes
+                .ok_or(Error::NotSupp)?;
+            result(unsafe {
+                write_bytes(ctx, offset, data.as_ptr(), data.len() as u32, pad, false)
+            })?;
+            Ok(())
+        })
+    }
+    /// Updates the NDEF message length (NLEN or TLV length) without touching the message.
+    pub fn write_raw_message_len(&mut self, len: u32) -> Result<()> {
+        self.retried(|poller| {
+            let ctx = poller.ctx_mut()?;
+            result(unsafe { rfal_sys::ndefPollerWriteRawMessageLen(ctx, len) })?;
+            Ok(())
+        })
+    }
+    /// Starts a transactional write of a `len` bytes message: the NDEF length is
+    /// reset to 0 and only set to `len` by [`WriteTransaction::commit`], so a tag
+    /// removed in the middle is left with an empty message rather than a corrupted one.
+    pub fn begin_write_message(&mut self, len: u32) -> Result<WriteTransaction<'_>> {
+        self.resume_write_message(WriteProgress {
+            written: 0,
+            total: len,
+        })
+    }
+    /// Restarts an interrupted transaction after the tag was re-activated and
+    /// its NDEF detected again, keeping the bytes already written.
+    pub fn resume_write_message(
+        &mut self,
+        progress: WriteProgress,
+    ) -> Result<WriteTransaction<'_>> {
+        if progress.written > progress.total {
+            return Err(Error::Param);
+        }
+        self.retried(|poller| {
+            let ctx = poller.ctx_mut()?;
+            result(unsafe { rfal_sys::ndefPollerCheckAvailableSpace(ctx, progress.total) })?;
+            result(unsafe { rfal_sys::ndefPollerBeginWriteMessage(ctx, progress.total) })?;
+            Ok(())
+        })?;
+        Ok(WriteTransaction {
+            poller: self,
+            progress,
+        })
+    }
+    /// Formats a blank tag, the capability container must match the tag type.
+    pub fn tag_format(&mut self, options: &FormatOptions) -> Result<()> {
+        self.retried(|poller| {
+            let ctx = poller.ctx_mut()?;
+            let cc = match options.cc {
+                Some(cc) if cc.dev_type() != ctx.type_ => return Err(Error::Param),
+                Some(cc) => Some(cc.to_raw()?),
+                None if ctx.type_ != ndefDeviceType::NDEF_DEV_T5T => return Err(Error::Param),
+                None => None,
+            };
+            let cc_ptr = cc.as_ref().map_or(core::ptr::null(), |cc| cc as *const _);
+            result(unsafe { rfal_sys::ndefPollerTagFormat(ctx, cc_ptr, options.options()) })?;
+            Ok(())
+        })
+    }
+    /// Sets the retry policy applied by the operations exchanging with the tag,
+    /// `None` disables the retries.
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy>) {
+        self.retry = policy.map(Retry::new);
+    }
+    pub fn retry_stats(&self) -> Option<&RetryStats> {
+        self.retry.as_ref().map(Retry::stats)
+    }
+    /// Runs `op` with the retries of `retry` instead of the policy of the poller,
+    /// the NDEF context is initialized and detected again after each reactivation.
+    pub fn with_retry<T>(
+        &mut self,
+        retry: &mut Retry,
+        op: impl FnMut(&mut Poller) -> Result<T>,
+    ) -> Result<T> {
+        let own = self.retry.take();
+        let res = retry.run(self, op, reactivated);
+        self.retry = own;
+        res
+    }
+    // the context is used in place, never copied, but the poller may have been
+    // moved since the last call: in addressed mode the T5T context points to the
+    // UID of the device given to Poller::initialize, which does not outlive it,
+    // point it to the copy held by the context itself before each use
+    fn ctx_mut(&mut self) -> Result<&mut rfal_sys::ndefContext> {
+        let ctx = self.ctx.as_mut().ok_or(Error::NotInitialized)?;
+        if ctx.type_ == ndefDeviceType::NDEF_DEV_T5T && unsafe { !ctx.subCtx.t5t.uid.is_null() } {
+            ctx.subCtx.t5t.uid = unsafe { ctx.device.dev.nfcv.InvRes.UID.as_ptr() };
+        }
+        Ok(ctx)
+    }
+    // the retry is taken out while running so that the operations run by `op`
+    // and by the reactivation are not tried again on their own
+    fn retried<T>(&mut self, mut op: impl FnMut(&mut Poller) -> Result<T>) -> Result<T> {
+        match self.retry.take() {
+            Some(mut retry) => {
+                let res = retry.run(self, op, reactivated);
+                self.retry = Some(retry);
+                res
             }
-            None => Err(Error::NotInitialized),
+            None => op(self),
+        }
+    }
+}
+
+/// How far a [`WriteTransaction`] went, to resume it with [`Poller::resume_write_message`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WriteProgress {
+    pub written: u32,
+    pub total: u32,
+}
+
+impl WriteProgress {
+    pub fn is_complete(&self) -> bool {
+        self.written == self.total
+    }
+}
+
+/// NDEF message being written, see [`Poller::begin_write_message`].
+pub struct WriteTransaction<'p> {
+    poller: &'p mut Poller,
+    progress: WriteProgress,
+}
+
+impl WriteTransaction<'_> {
+    pub fn progress(&self) -> WriteProgress {
+        self.progress
+    }
+    /// Appends `chunk` to the message.
+    pub fn write(&mut self, chunk: &[u8]) -> Result<()> {
+        if chunk.is_empty() {
+            return Ok(());
         }
+        if chunk.len() as u32 > self.progress.total - self.progress.written {
+            return Err(Error::Param);
+        }
+        let offset = self
+            .poller
+            .ctx
+            .as_ref()
+            .ok_or(Error::NotInitialized)?
+            .messageOffset
+            + self.progress.written;
+        self.poller.write_bytes(offset, chunk, false)?;
+        self.progress.written += chunk.len() as u32;
+        Ok(())
     }
+    /// Appends every chunk yielded by `chunks`.
+    pub fn write_chunks<'c>(&mut self, chunks: impl IntoIterator<Item = &'c [u8]>) -> Result<()> {
+        chunks.into_iter().try_for_each(|chunk| self.write(chunk))
+    }
+    /// Appends what `reader` yields until the end of the message, using `buf` as chunk buffer.
+    #[cfg(feature = "embedded-io")]
+    pub fn write_from<R: embedded_io::Read>(
+        &mut self,
+        reader: &mut R,
+        buf: &mut [u8],
+    ) -> Result<()> {
+        while !self.progress.is_complete() {
+            let max = buf
+                .len()
+                .min((self.progress.total - self.progress.written) as usize);
+            let len = reader.read(&mut buf[..max]).map_err(|_| Error::Io)?;
+            if len == 0 {
+                return Err(Error::Io);
+            }
+            self.write(&buf[..len])?;
+        }
+        Ok(())
+    }
+    /// Writes the final NDEF length once the whole message has been written.
+    pub fn commit(self) -> Result<()> {
+        if !self.progress.is_complete() {
+            return Err(Error::WrongState);
+        }
+        let total = self.progress.total;
+        self.poller.retried(|poller| {
+            let ctx = poller.ctx_mut()?;
+            result(unsafe { rfal_sys::ndefPollerEndWriteMessage(ctx, total) })?;
+            Ok(())
+        })
+    }
+}
+
+// brings the NDEF context back after a reactivation by a retry
+fn reactivated(poller: &mut Poller) -> Result<()> {
+    poller.initialize(&Nfc::default().active_device()?)?;
+    poller.ndef_detect().map(|_| ())
 }
@@ -6,7 +6,10 @@ use alloc::vec::Vec;
 #[cfg(not(feature = "alloc"))]
 use heapless::Vec;
 
-use crate::{result, rfalNfcDevType, rfalNfcState, rfalNfcaListenDevice, Result};
+use crate::{
+    result, rfalNfcDevType, rfalNfcState, rfalNfcaListenDevice, Error, NfcbDevice, NfcfDevice,
+    NfcvDevice, Result, Retry, RetryPolicy, RetryStats, St25tbDevice, WakeUp, WakeUpConfig,
+};
 use rfal_sys::rfalNfcDevice;
 
 pub struct Device(pub(crate) rfalNfcDevice);
@@ -15,6 +18,18 @@ impl Device {
     pub fn nfca(&self) -> rfalNfcaListenDevice {
         unsafe { self.0.dev.nfca }
     }
+    pub fn nfcb(&self) -> NfcbDevice {
+        NfcbDevice(unsafe { self.0.dev.nfcb })
+    }
+    pub fn nfcf(&self) -> NfcfDevice {
+        NfcfDevice(unsafe { self.0.dev.nfcf })
+    }
+    pub fn nfcv(&self) -> NfcvDevice {
+        NfcvDevice(unsafe { self.0.dev.nfcv })
+    }
+    pub fn st25tb(&self) -> St25tbDevice {
+        St25tbDevice(unsafe { self.0.dev.st25tb })
+    }
     pub fn id(&self) -> Option<&[u8]> {
         if self.0.nfcidLen != 0 {
             Some(unsafe { core::slice::from_raw_parts(self.0.nfcid, self.0.nfcidLen as usize) })
@@ -30,6 +45,7 @@ impl Device {
 #[derive(Default)]
 pub struct Nfc {
     pub data_exchange: DataExchange,
+    retry: Option<Retry>,
 }
 
 impl Nfc {
@@ -70,6 +86,26 @@ impl Nfc {
         };
         Ok(devices)
     }
+    /// Replaces the content of `devices` with the devices found by the last
+    /// discovery, fails with `Error::NoMem` when more than `N` were found.
+    pub fn get_devices_found_into<const N: usize>(
+        &self,
+        devices: &mut heapless::Vec<Device, N>,
+    ) -> Result<()> {
+        let mut dev_list: *mut rfalNfcDevice = core::ptr::null_mut();
+        let mut dev_cnt: u8 = 0;
+        result(unsafe { rfal_sys::rfalNfcGetDevicesFound(&mut dev_list, &mut dev_cnt) })?;
+        devices.clear();
+        for &d in unsafe { core::slice::from_raw_parts(dev_list, dev_cnt as usize) } {
+            devices.push(Device(d)).map_err(|_| Error::NoMem)?;
+        }
+        Ok(())
+    }
+    /// Whether NFC-B devices still collided with the max number of slots in the
+    /// last discovery, a discovery with more slots may find them.
+    pub fn nfcb_collision_pending(&self) -> bool {
+        unsafe { rfal_sys::rfalNfcGetNfcbCollisionPending() }
+    }
     pub fn select(&self, dev_idx: u8) -> Result<()> {
         result(unsafe { rfal_sys::rfalNfcSelect(dev_idx) })
     }
@@ -95,11 +131,32 @@ impl Nfc {
             )
         })
     }
-    pub fn enter_wakeup_mode(&self) -> Result<()> {
-        result(unsafe { rfal_sys::rfalWakeUpModeStart(core::ptr::null()) })
+    /// Starts the wake-up mode with `config`, or the RFAL default one.
+    pub fn enter_wakeup_mode(&self, config: Option<&WakeUpConfig>) -> Result<()> {
+        WakeUp::start(config)
     }
     pub fn exit_wakeup_mode(&self) -> Result<()> {
-        result(unsafe { rfal_sys::rfalWakeUpModeStop() })
+        WakeUp::stop()
+    }
+    /// Sets the retry policy applied by [`Nfc::exchange`], `None` disables the retries.
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy>) {
+        self.retry = policy.map(Retry::new);
+    }
+    pub fn retry_stats(&self) -> Option<&RetryStats> {
+        self.retry.as_ref().map(Retry::stats)
+    }
+    /// Sends `tx_data` to the active device and waits for its answer, tried
+    /// again as told by the policy set with [`Nfc::set_retry_policy`].
+    pub fn exchange(&mut self, tx_data: &mut [u8], fwt: u32) -> Result<&'static [u8]> {
+        let mut exchange = |data_exchange: &mut DataExchange| {
+            data_exchange.start(Some(&mut *tx_data), fwt)?;
+            data_exchange.wait()
+        };
+        match self.retry.as_mut() {
+            Some(retry) => retry.run(&mut self.data_exchange, exchange, |_| Ok(()))?,
+            None => exchange(&mut self.data_exchange)?,
+        }
+        Ok(self.data_exchange.rx_data())
     }
 }
 
@@ -135,6 +192,18 @@ impl DataExchange {
     pub fn get_status(&self) -> Result<()> {
         result(unsafe { rfal_sys::rfalNfcDataExchangeGetStatus() })
     }
+    /// Runs the worker until the exchange is done.
+    pub fn wait(&self) -> Result<()> {
+        loop {
+            unsafe {
+                rfal_sys::rfalNfcWorker();
+            }
+            match self.get_status() {
+                Err(Error::Busy) => continue,
+                res => return res,
+            }
+        }
+    }
     pub fn rx_data(&self) -> &'static [u8] {
         let len = unsafe { *self.rcv_len_ptr };
         unsafe { core::slice::from_raw_parts(self.rx_data_ptr, len as usize) }
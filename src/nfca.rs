@@ -0,0 +1,378 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{result, us_to_1fc, Error, Result};
+use rfal_sys::{
+    rfal14443AShortFrameCmd, rfalComplianceMode, rfalNfcaListenDevice, rfalNfcaListenDeviceType,
+    rfalNfcaSelRes, rfalNfcaSensRes,
+};
+
+pub const NFCA_CASCADE_UID_LEN: usize = rfal_sys::RFAL_NFCA_CASCADE_1_UID_LEN as usize;
+pub const NFCA_UID_MAX_LEN: usize = rfal_sys::RFAL_NFCA_CASCADE_3_UID_LEN as usize;
+/// SEL_CMD, SEL_PAR, NFCID1 of a cascade level and BCC.
+pub const NFCA_SEL_REQ_LEN: usize = 2 + NFCA_CASCADE_UID_LEN + 1;
+
+// SEL_CMD and SEL_PAR
+const SDD_REQ_LEN: u8 = 2;
+// SEL_PAR of a SEL_REQ, 7 bytes and no bit
+const SEL_PAR_SELECT: u8 = 0x70;
+// SAK bit set while the NFCID1 is not complete
+const SAK_CASCADE: u8 = 0x04;
+// ATQA bits b5 to b1, bit frame anticollision
+const SENS_RES_SDD_MASK: u8 = 0x1F;
+
+/// SENS_RES (ATQA) answered to a REQA or WUPA.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SensRes {
+    pub anticollision_info: u8,
+    pub platform_info: u8,
+}
+
+impl SensRes {
+    /// NFCID1 length announced by the card, `None` for the RFU size.
+    pub fn uid_len(&self) -> Option<usize> {
+        match self.anticollision_info >> 6 {
+            0 => Some(rfal_sys::RFAL_NFCA_CASCADE_1_UID_LEN as usize),
+            1 => Some(rfal_sys::RFAL_NFCA_CASCADE_2_UID_LEN as usize),
+            2 => Some(rfal_sys::RFAL_NFCA_CASCADE_3_UID_LEN as usize),
+            _ => None,
+        }
+    }
+    /// Whether the card supports the bit frame anticollision, T1T do not.
+    pub fn is_bit_frame_sdd(&self) -> bool {
+        self.anticollision_info & SENS_RES_SDD_MASK != 0
+    }
+    pub fn is_t1t(&self) -> bool {
+        self.platform_info & rfal_sys::RFAL_NFCA_SENS_RES_PLATFORM_MASK as u8
+            == rfal_sys::RFAL_NFCA_SENS_RES_PLATFORM_T1T as u8
+    }
+}
+
+impl From<rfalNfcaSensRes> for SensRes {
+    fn from(value: rfalNfcaSensRes) -> Self {
+        Self {
+            anticollision_info: value.anticollisionInfo,
+            platform_info: value.platformInfo,
+        }
+    }
+}
+
+/// SEL_RES (SAK) answered to a SEL_REQ.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SelRes {
+    pub sak: u8,
+}
+
+impl SelRes {
+    /// Whether the NFCID1 is complete, otherwise the next cascade level follows.
+    pub fn is_uid_complete(&self) -> bool {
+        self.sak & SAK_CASCADE == 0
+    }
+    /// Platform configured by the card, only meaningful once the NFCID1 is complete.
+    pub fn listen_type(&self) -> rfalNfcaListenDeviceType {
+        match self.sak & rfal_sys::RFAL_NFCA_SEL_RES_CONF_MASK as u8 {
+            0x20 => rfalNfcaListenDeviceType::RFAL_NFCA_T4T,
+            0x40 => rfalNfcaListenDeviceType::RFAL_NFCA_NFCDEP,
+            0x60 => rfalNfcaListenDeviceType::RFAL_NFCA_T4T_NFCDEP,
+            _ => rfalNfcaListenDeviceType::RFAL_NFCA_T2T,
+        }
+    }
+}
+
+impl From<rfalNfcaSelRes> for SelRes {
+    fn from(value: rfalNfcaSelRes) -> Self {
+        Self { sak: value.sak }
+    }
+}
+
+/// NFCID1 (UID) of 4, 7 or 10 bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Nfcid1 {
+    len: u8,
+    bytes: [u8; NFCA_UID_MAX_LEN],
+}
+
+impl Nfcid1 {
+    pub fn new(uid: &[u8]) -> Result<Self> {
+        if ![4, 7, 10].contains(&uid.len()) {
+            return Err(Error::Param);
+        }
+        let mut bytes = [0u8; NFCA_UID_MAX_LEN];
+        bytes[..uid.len()].copy_from_slice(uid);
+        Ok(Self {
+            len: uid.len() as u8,
+            bytes,
+        })
+    }
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+    /// Number of cascade levels needed to select the card.
+    pub fn cascade_levels(&self) -> u8 {
+        match self.len {
+            4 => 1,
+            7 => 2,
+            10 => 3,
+            _ => 0,
+        }
+    }
+}
+
+/// Cascade level of an anticollision or select.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CascadeLevel {
+    One,
+    Two,
+    Three,
+}
+
+impl CascadeLevel {
+    /// SEL_CMD of the level.
+    pub fn sel_cmd(&self) -> u8 {
+        match self {
+            CascadeLevel::One => 0x93,
+            CascadeLevel::Two => 0x95,
+            CascadeLevel::Three => 0x97,
+        }
+    }
+}
+
+/// SDD_REQ of a cascade level with the NFCID1 bits already known, completed by
+/// the SDD_RES on reception.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AnticollisionFrame {
+    buf: [u8; NFCA_SEL_REQ_LEN],
+    bytes: u8,
+    bits: u8,
+}
+
+impl AnticollisionFrame {
+    pub fn new(level: CascadeLevel) -> Self {
+        let mut buf = [0u8; NFCA_SEL_REQ_LEN];
+        buf[0] = level.sel_cmd();
+        Self {
+            buf,
+            bytes: SDD_REQ_LEN,
+            bits: 0,
+        }
+    }
+    /// Number of NFCID1 bits sent with the request, updated to the bits
+    /// received before a collision.
+    pub fn known_bits(&self) -> usize {
+        (self.bytes - SDD_REQ_LEN) as usize * 8 + self.bits as usize
+    }
+    /// Appends one bit to the known NFCID1, to choose a card after a collision.
+    pub fn push_bit(&mut self, bit: bool) -> Result<()> {
+        if self.bytes as usize >= NFCA_SEL_REQ_LEN - 1 {
+            return Err(Error::Param);
+        }
+        let byte = &mut self.buf[self.bytes as usize];
+        *byte &= !(0xFF << self.bits);
+        *byte |= (bit as u8) << self.bits;
+        self.bits += 1;
+        if self.bits == 8 {
+            self.bits = 0;
+            self.bytes += 1;
+        }
+        Ok(())
+    }
+    /// NFCID1 part of the cascade level, complete after a successful exchange.
+    pub fn nfcid1(&self) -> [u8; NFCA_CASCADE_UID_LEN] {
+        let mut nfcid1 = [0u8; NFCA_CASCADE_UID_LEN];
+        nfcid1.copy_from_slice(&self.buf[2..2 + NFCA_CASCADE_UID_LEN]);
+        nfcid1
+    }
+    pub fn bcc(&self) -> u8 {
+        self.buf[NFCA_SEL_REQ_LEN - 1]
+    }
+    pub fn is_bcc_valid(&self) -> bool {
+        self.nfcid1().iter().fold(0, |bcc, b| bcc ^ b) == self.bcc()
+    }
+    /// SEL_REQ of the resolved cascade level, to send with CRC.
+    pub fn sel_req(&self) -> [u8; NFCA_SEL_REQ_LEN] {
+        let mut sel_req = self.buf;
+        sel_req[1] = SEL_PAR_SELECT;
+        sel_req
+    }
+}
+
+/// NFC-A device found by the collision resolution.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct NfcaDevice(pub(crate) rfalNfcaListenDevice);
+
+impl Default for NfcaDevice {
+    fn default() -> Self {
+        // allocate default values manually, thanks bingen to not deriving Default trait...
+        Self(rfalNfcaListenDevice {
+            type_: rfalNfcaListenDeviceType::RFAL_NFCA_T2T,
+            sensRes: rfalNfcaSensRes {
+                anticollisionInfo: 0,
+                platformInfo: 0,
+            },
+            selRes: rfalNfcaSelRes { sak: 0 },
+            nfcId1Len: 0,
+            nfcId1: [0; NFCA_UID_MAX_LEN],
+            ridRes: rfal_sys::rfalT1TRidRes {
+                hr0: 0,
+                hr1: 0,
+                uid: [0; rfal_sys::RFAL_T1T_UID_LEN as usize],
+            },
+            isSleep: false,
+        })
+    }
+}
+
+impl From<rfalNfcaListenDevice> for NfcaDevice {
+    fn from(value: rfalNfcaListenDevice) -> Self {
+        Self(value)
+    }
+}
+
+impl NfcaDevice {
+    pub fn listen_type(&self) -> rfalNfcaListenDeviceType {
+        self.0.type_
+    }
+    pub fn sens_res(&self) -> SensRes {
+        self.0.sensRes.into()
+    }
+    pub fn sel_res(&self) -> SelRes {
+        self.0.selRes.into()
+    }
+    /// NFCID1, empty for a T1T which has no anticollision.
+    pub fn nfcid1(&self) -> Nfcid1 {
+        Nfcid1::new(&self.0.nfcId1[..self.0.nfcId1Len as usize]).unwrap_or_default()
+    }
+    pub fn is_sleeping(&self) -> bool {
+        self.0.isSleep
+    }
+}
+
+/// NFC-A (ISO 14443-A) poller commands.
+pub struct Nfca;
+
+impl Nfca {
+    /// Sets the RF configuration for NFC-A polling, not needed after a discovery.
+    pub fn initialize() -> Result<()> {
+        result(unsafe { rfal_sys::rfalNfcaPollerInitialize() })
+    }
+    /// Sends a REQA or WUPA and returns the SENS_RES, collisions are ignored.
+    pub fn check_presence(cmd: rfal14443AShortFrameCmd) -> Result<SensRes> {
+        let mut sens_res = rfalNfcaSensRes {
+            anticollisionInfo: 0,
+            platformInfo: 0,
+        };
+        result(unsafe { rfal_sys::rfalNfcaPollerCheckPresence(cmd, &mut sens_res) })?;
+        Ok(sens_res.into())
+    }
+    /// Technology detection as defined by `comp_mode`, with the field on and its GT.
+    pub fn technology_detection(comp_mode: rfalComplianceMode) -> Result<SensRes> {
+        let mut sens_res = rfalNfcaSensRes {
+            anticollisionInfo: 0,
+            platformInfo: 0,
+        };
+        result(unsafe { rfal_sys::rfalNfcaPollerTechnologyDetection(comp_mode, &mut sens_res) })?;
+        Ok(sens_res.into())
+    }
+    /// Sends a raw REQA or WUPA, unlike [`Nfca::check_presence`] a collision is
+    /// returned as `RfCollision`.
+    pub fn transceive_short_frame(cmd: rfal14443AShortFrameCmd, fwt_us: u32) -> Result<SensRes> {
+        let mut rx = [0u8; 2];
+        let mut rcv_bits = 0u16;
+        result(unsafe {
+            rfal_sys::rfalISO14443ATransceiveShortFrame(
+                cmd,
+                rx.as_mut_ptr(),
+                (rx.len() * 8) as u8,
+                &mut rcv_bits,
+                us_to_1fc(fwt_us),
+            )
+        })?;
+        if rcv_bits as usize != rx.len() * 8 {
+            return Err(Error::Proto);
+        }
+        Ok(SensRes {
+            anticollision_info: rx[0],
+            platform_info: rx[1],
+        })
+    }
+    /// Sends the SDD_REQ of `frame` and completes it with the SDD_RES. On
+    /// `RfCollision`, `frame` only keeps the bits received before the collision.
+    pub fn transceive_anticollision_frame(
+        frame: &mut AnticollisionFrame,
+        fwt_us: u32,
+    ) -> Result<()> {
+        let mut rcv_bits = 0u16;
+        frame.buf[1] = (frame.bytes << 4) | frame.bits;
+        result(unsafe {
+            rfal_sys::rfalISO14443ATransceiveAnticollisionFrame(
+                frame.buf.as_mut_ptr(),
+                &mut frame.bytes,
+                &mut frame.bits,
+                &mut rcv_bits,
+                us_to_1fc(fwt_us),
+            )
+        })
+    }
+    /// Resolves and selects one card, returns its NFCID1, last SEL_RES and whether
+    /// other cards are still pending. A `dev_limit` of 0 only detects collisions.
+    pub fn single_collision_resolution(dev_limit: u8) -> Result<(Nfcid1, SelRes, bool)> {
+        let mut col_pending = false;
+        let mut sel_res = rfalNfcaSelRes { sak: 0 };
+        let mut nfcid1 = [0u8; NFCA_UID_MAX_LEN];
+        let mut nfcid1_len = 0u8;
+        result(unsafe {
+            rfal_sys::rfalNfcaPollerSingleCollisionResolution(
+                dev_limit,
+                &mut col_pending,
+                &mut sel_res,
+                nfcid1.as_mut_ptr(),
+                &mut nfcid1_len,
+            )
+        })?;
+        let nfcid1 = Nfcid1::new(&nfcid1[..nfcid1_len as usize]).map_err(|_| Error::Proto)?;
+        Ok((nfcid1, sel_res.into(), col_pending))
+    }
+    /// Runs the full collision resolution, fills `devices` and returns the number
+    /// found, all but the last one are left sleeping.
+    pub fn full_collision_resolution(
+        comp_mode: rfalComplianceMode,
+        devices: &mut [NfcaDevice],
+    ) -> Result<usize> {
+        let mut dev_cnt = 0u8;
+        result(unsafe {
+            rfal_sys::rfalNfcaPollerFullCollisionResolution(
+                comp_mode,
+                devices.len().min(u8::MAX as usize) as u8,
+                devices.as_mut_ptr() as *mut rfalNfcaListenDevice,
+                &mut dev_cnt,
+            )
+        })?;
+        Ok(dev_cnt as usize)
+    }
+    /// Same as [`Nfca::full_collision_resolution`] but also puts the last card
+    /// to sleep, following the NFC Forum Activity.
+    pub fn sleep_full_collision_resolution(devices: &mut [NfcaDevice]) -> Result<usize> {
+        let mut dev_cnt = 0u8;
+        result(unsafe {
+            rfal_sys::rfalNfcaPollerSleepFullCollisionResolution(
+                devices.len().min(u8::MAX as usize) as u8,
+                devices.as_mut_ptr() as *mut rfalNfcaListenDevice,
+                &mut dev_cnt,
+            )
+        })?;
+        Ok(dev_cnt as usize)
+    }
+    /// Selects the card with `nfcid1` through all its cascade levels.
+    pub fn select(nfcid1: &Nfcid1) -> Result<SelRes> {
+        let mut sel_res = rfalNfcaSelRes { sak: 0 };
+        result(unsafe {
+            rfal_sys::rfalNfcaPollerSelect(nfcid1.bytes.as_ptr(), nfcid1.len, &mut sel_res)
+        })?;
+        Ok(sel_res.into())
+    }
+    /// SLP_REQ (HLTA), puts the selected card to sleep.
+    pub fn sleep() -> Result<()> {
+        result(unsafe { rfal_sys::rfalNfcaPollerSleep() })
+    }
+}
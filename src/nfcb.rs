@@ -0,0 +1,229 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{result, Error, Result};
+use rfal_sys::{
+    rfalBitRate, rfalComplianceMode, rfalNfcbListenDevice, rfalNfcbSensCmd, rfalNfcbSensbRes,
+    rfalNfcbSlots,
+};
+
+pub const NFCB_PUPI_LEN: usize = rfal_sys::RFAL_NFCB_NFCID0_LEN as usize;
+
+// FSC for each FSCI, values past the table are RFU
+const FSC: [usize; 13] = [16, 24, 32, 40, 48, 64, 96, 128, 256, 512, 1024, 2048, 4096];
+
+/// NFC-B device answering a SENSB_REQ.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct NfcbDevice(pub(crate) rfalNfcbListenDevice);
+
+impl Default for NfcbDevice {
+    fn default() -> Self {
+        // allocate default values manually, thanks bingen to not deriving Default trait...
+        Self(rfalNfcbListenDevice {
+            sensbResLen: 0,
+            sensbRes: empty_sensb_res(),
+            isSleep: false,
+        })
+    }
+}
+
+impl NfcbDevice {
+    pub fn pupi(&self) -> [u8; NFCB_PUPI_LEN] {
+        self.0.sensbRes.nfcid0
+    }
+    pub fn sensb_res(&self) -> SensbRes {
+        SensbRes::decode(&self.0.sensbRes, self.0.sensbResLen)
+    }
+    /// Whether the card is ISO-DEP compliant, discovery then activates it with ATTRIB.
+    pub fn is_iso_dep(&self) -> bool {
+        self.sensb_res().protocol.iso_dep
+    }
+    pub fn is_sleeping(&self) -> bool {
+        self.0.isSleep
+    }
+}
+
+/// Application Data of the SENSB_RES.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NfcbAppData {
+    pub afi: u8,
+    /// CRC_B of the AID.
+    pub crc_b: [u8; 2],
+    pub num_apps: u8,
+}
+
+/// Protocol Info of the SENSB_RES.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NfcbProtocolInfo {
+    /// Only the same bit rate in both directions is supported.
+    pub same_bit_rate: bool,
+    /// Supported listener to poller bit rates: bit 0 for 212, 1 for 424 and 2 for 848 kbps.
+    pub listen_to_poll: u8,
+    /// Supported poller to listener bit rates, same encoding as `listen_to_poll`.
+    pub poll_to_listen: u8,
+    pub fsci: u8,
+    pub iso_dep: bool,
+    /// Minimum TR2 code, see Digital Table 35.
+    pub tr2: u8,
+    pub fwi: u8,
+    /// ADC, advanced protocol features supported.
+    pub advanced_features: bool,
+    /// ADC, proprietary application data coding.
+    pub proprietary_app: bool,
+    /// FO, NAD supported.
+    pub nad: bool,
+    /// FO, DID supported.
+    pub did: bool,
+    /// Only present in an extended SENSB_RES.
+    pub sfgi: Option<u8>,
+}
+
+impl NfcbProtocolInfo {
+    /// Maximum frame size the card accepts.
+    pub fn fsc(&self) -> usize {
+        FSC[(self.fsci as usize).min(FSC.len() - 1)]
+    }
+    /// Frame waiting time in 1/fc.
+    pub fn fwt(&self) -> u32 {
+        // FWI 15 is RFU and means 4
+        let fwi = if self.fwi == 15 { 4 } else { self.fwi };
+        (256 * 16) << fwi
+    }
+    /// Highest bit rate supported in both directions.
+    pub fn max_bit_rate(&self) -> rfalBitRate {
+        let both = self.listen_to_poll & self.poll_to_listen;
+        if both & 0x04 != 0 {
+            rfalBitRate::RFAL_BR_848
+        } else if both & 0x02 != 0 {
+            rfalBitRate::RFAL_BR_424
+        } else if both & 0x01 != 0 {
+            rfalBitRate::RFAL_BR_212
+        } else {
+            rfalBitRate::RFAL_BR_106
+        }
+    }
+}
+
+/// Decoded SENSB_RES.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SensbRes {
+    pub pupi: [u8; NFCB_PUPI_LEN],
+    pub app_data: NfcbAppData,
+    pub protocol: NfcbProtocolInfo,
+}
+
+impl SensbRes {
+    fn decode(res: &rfalNfcbSensbRes, len: u8) -> Self {
+        let info = &res.protInfo;
+        Self {
+            pupi: res.nfcid0,
+            app_data: NfcbAppData {
+                afi: res.appData.AFI,
+                crc_b: res.appData.CRC_B,
+                num_apps: res.appData.numApps,
+            },
+            protocol: NfcbProtocolInfo {
+                same_bit_rate: info.BRC & 0x80 != 0,
+                listen_to_poll: (info.BRC >> 4) & 0x07,
+                poll_to_listen: info.BRC & 0x07,
+                fsci: info.FsciProType >> 4,
+                iso_dep: info.FsciProType & 0x01 != 0,
+                tr2: (info.FsciProType >> 1) & 0x03,
+                fwi: info.FwiAdcFo >> 4,
+                advanced_features: info.FwiAdcFo & 0x08 != 0,
+                proprietary_app: info.FwiAdcFo & 0x04 != 0,
+                nad: info.FwiAdcFo & 0x02 != 0,
+                did: info.FwiAdcFo & 0x01 != 0,
+                sfgi: (len as u32 == rfal_sys::RFAL_NFCB_SENSB_RES_EXT_LEN)
+                    .then_some(info.SFGI >> 4),
+            },
+        }
+    }
+}
+
+/// NFC-B (ISO 14443-B) poller commands.
+pub struct Nfcb;
+
+impl Nfcb {
+    /// Sets the RF configuration for NFC-B polling, not needed after a discovery.
+    pub fn initialize() -> Result<()> {
+        result(unsafe { rfal_sys::rfalNfcbPollerInitialize() })
+    }
+    /// Same as [`Nfcb::initialize`] with the AFI and PARAM of the SENSB_REQ.
+    pub fn initialize_with_params(afi: u8, param: u8) -> Result<()> {
+        result(unsafe { rfal_sys::rfalNfcbPollerInitializeWithParams(afi, param) })
+    }
+    /// Sends a SENSB_REQ or ALLB_REQ and returns the first SENSB_RES.
+    pub fn check_presence(cmd: rfalNfcbSensCmd, slots: rfalNfcbSlots) -> Result<SensbRes> {
+        let mut res = empty_sensb_res();
+        let mut len = 0u8;
+        result(unsafe { rfal_sys::rfalNfcbPollerCheckPresence(cmd, slots, &mut res, &mut len) })?;
+        if (len as u32) < rfal_sys::RFAL_NFCB_SENSB_RES_LEN {
+            return Err(Error::Proto);
+        }
+        Ok(SensbRes::decode(&res, len))
+    }
+    /// SLPB_REQ, puts the card with `pupi` to sleep.
+    pub fn sleep(pupi: &[u8; NFCB_PUPI_LEN]) -> Result<()> {
+        result(unsafe { rfal_sys::rfalNfcbPollerSleep(pupi.as_ptr()) })
+    }
+    /// Runs the collision resolution from 1 up to 16 slots, fills `devices`
+    /// and returns the number found.
+    pub fn collision_resolution(
+        comp_mode: rfalComplianceMode,
+        devices: &mut [NfcbDevice],
+    ) -> Result<usize> {
+        let mut dev_cnt = 0u8;
+        result(unsafe {
+            rfal_sys::rfalNfcbPollerCollisionResolution(
+                comp_mode,
+                devices.len().min(u8::MAX as usize) as u8,
+                devices.as_mut_ptr() as *mut rfalNfcbListenDevice,
+                &mut dev_cnt,
+            )
+        })?;
+        Ok(dev_cnt as usize)
+    }
+    /// Runs the collision resolution from `init_slots` up to `end_slots`, returns
+    /// the number of devices found and whether a collision is still pending.
+    pub fn slotted_collision_resolution(
+        comp_mode: rfalComplianceMode,
+        init_slots: rfalNfcbSlots,
+        end_slots: rfalNfcbSlots,
+        devices: &mut [NfcbDevice],
+    ) -> Result<(usize, bool)> {
+        let mut dev_cnt = 0u8;
+        let mut col_pending = false;
+        result(unsafe {
+            rfal_sys::rfalNfcbPollerSlottedCollisionResolution(
+                comp_mode,
+                devices.len().min(u8::MAX as usize) as u8,
+                init_slots,
+                end_slots,
+                devices.as_mut_ptr() as *mut rfalNfcbListenDevice,
+                &mut dev_cnt,
+                &mut col_pending,
+            )
+        })?;
+        Ok((dev_cnt as usize, col_pending))
+    }
+}
+
+fn empty_sensb_res() -> rfalNfcbSensbRes {
+    rfalNfcbSensbRes {
+        cmd: 0,
+        nfcid0: [0; NFCB_PUPI_LEN],
+        appData: rfal_sys::rfalNfcbSensbResAppData {
+            AFI: 0,
+            CRC_B: [0; 2],
+            numApps: 0,
+        },
+        protInfo: rfal_sys::rfalNfcbSensbResProtocolInfo {
+            BRC: 0,
+            FsciProType: 0,
+            FwiAdcFo: 0,
+            SFGI: 0,
+        },
+    }
+}
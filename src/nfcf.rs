@@ -0,0 +1,295 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{result, Device, Error, Result};
+use rfal_sys::{
+    rfalBitRate, rfalComplianceMode, rfalFeliCaPollRes, rfalFeliCaPollSlots, rfalNfcDevType,
+    rfalNfcfBlockListElem, rfalNfcfListenDevice, rfalNfcfServBlockListParam,
+};
+
+pub const FELICA_NFCID2_LEN: usize = rfal_sys::RFAL_NFCF_NFCID2_LEN as usize;
+pub const FELICA_PMM_LEN: usize = 8;
+pub const FELICA_BLOCK_LEN: usize = rfal_sys::RFAL_NFCF_BLOCK_LEN as usize;
+/// Blocks read by one Check command.
+pub const FELICA_CHECK_MAX_BLOCKS: usize = 15;
+/// Blocks written by one Update command.
+pub const FELICA_UPDATE_MAX_BLOCKS: usize = 13;
+
+const POLL_RES_LEN: usize = rfal_sys::RFAL_FELICA_POLL_RES_LEN as usize;
+const POLL_MAX_SLOTS: usize = rfal_sys::RFAL_FELICA_POLL_MAX_SLOTS as usize;
+// LEN, CMD, NFCID2 and PMm, followed by the optional Request Data
+const POLL_RES_RD_POS: usize = 18;
+// Check/Update response: LEN, CMD, NFCID2, status flags and number of blocks
+const CHECK_RES_HEADER_LEN: usize = 13;
+// Update request: CMD, NFCID2, one service, block list and block data
+const UPDATE_REQ_MAX_LEN: usize = 13
+    + FELICA_UPDATE_MAX_BLOCKS
+        * (rfal_sys::RFAL_NFCF_BLOCKLISTELEM_MAX_LEN as usize + FELICA_BLOCK_LEN);
+const UPDATE_RES_LEN: usize = 12;
+
+/// NFC-F device answering a SENSF_REQ.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct NfcfDevice(pub(crate) rfalNfcfListenDevice);
+
+impl Default for NfcfDevice {
+    fn default() -> Self {
+        // allocate default values manually, thanks bingen to not deriving Default trait...
+        Self(rfalNfcfListenDevice {
+            sensfResLen: 0,
+            sensfRes: rfal_sys::rfalNfcfSensfRes {
+                CMD: 0,
+                NFCID2: [0; FELICA_NFCID2_LEN],
+                PAD0: [0; 2],
+                PAD1: [0; 3],
+                MRTIcheck: 0,
+                MRTIupdate: 0,
+                PAD2: 0,
+                RD: [0; 2],
+            },
+        })
+    }
+}
+
+impl NfcfDevice {
+    /// NFCID2, the FeliCa IDm.
+    pub fn nfcid2(&self) -> [u8; FELICA_NFCID2_LEN] {
+        self.0.sensfRes.NFCID2
+    }
+    /// PAD0 to PAD2 with the MRTI bytes, the FeliCa PMm.
+    pub fn pmm(&self) -> [u8; FELICA_PMM_LEN] {
+        let res = &self.0.sensfRes;
+        [
+            res.PAD0[0],
+            res.PAD0[1],
+            res.PAD1[0],
+            res.PAD1[1],
+            res.PAD1[2],
+            res.MRTIcheck,
+            res.MRTIupdate,
+            res.PAD2,
+        ]
+    }
+    /// Request Data, the system code when polled with a request code of 1.
+    pub fn request_data(&self) -> Option<[u8; 2]> {
+        (self.0.sensfResLen as u32 == rfal_sys::RFAL_NFCF_SENSF_RES_LEN_MAX)
+            .then_some(self.0.sensfRes.RD)
+    }
+    /// NFCID2 starting with 01FEh, an NFC-DEP device rather than a T3T.
+    pub fn is_nfc_dep(&self) -> bool {
+        self.0.sensfRes.NFCID2[..2] == [0x01, 0xFE]
+    }
+}
+
+/// Response to a FeliCa Polling command.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FelicaPollRes {
+    pub nfcid2: [u8; FELICA_NFCID2_LEN],
+    pub pmm: [u8; FELICA_PMM_LEN],
+    pub request_data: Option<[u8; 2]>,
+}
+
+impl FelicaPollRes {
+    fn decode(res: &rfalFeliCaPollRes) -> Self {
+        let mut nfcid2 = [0u8; FELICA_NFCID2_LEN];
+        let mut pmm = [0u8; FELICA_PMM_LEN];
+        nfcid2.copy_from_slice(&res[2..2 + FELICA_NFCID2_LEN]);
+        pmm.copy_from_slice(&res[2 + FELICA_NFCID2_LEN..POLL_RES_RD_POS]);
+        Self {
+            nfcid2,
+            pmm,
+            request_data: (res[0] as usize == POLL_RES_LEN)
+                .then_some([res[POLL_RES_RD_POS], res[POLL_RES_RD_POS + 1]]),
+        }
+    }
+}
+
+/// FeliCa (NFC-F) commands without encryption, as used by Type 3 Tags.
+pub struct Felica {
+    nfcid2: [u8; FELICA_NFCID2_LEN],
+}
+
+impl Felica {
+    /// Sets the RF configuration for NFC-F polling at 212 or 424 kbps, not needed
+    /// after a discovery.
+    pub fn initialize(bit_rate: rfalBitRate) -> Result<()> {
+        result(unsafe { rfal_sys::rfalNfcfPollerInitialize(bit_rate) })
+    }
+    pub fn check_presence() -> Result<()> {
+        result(unsafe { rfal_sys::rfalNfcfPollerCheckPresence() })
+    }
+    /// Polling with `system_code` and `request_code` over `slots` time slots, fills
+    /// `responses` and returns the number of responses and of slots with a collision.
+    pub fn poll(
+        slots: rfalFeliCaPollSlots,
+        system_code: u16,
+        request_code: u8,
+        responses: &mut [FelicaPollRes],
+    ) -> Result<(usize, usize)> {
+        let mut res = [[0u8; POLL_RES_LEN]; POLL_MAX_SLOTS];
+        let mut dev_cnt = 0u8;
+        let mut collisions = 0u8;
+        result(unsafe {
+            rfal_sys::rfalNfcfPollerPoll(
+                slots,
+                system_code,
+                request_code,
+                res.as_mut_ptr(),
+                &mut dev_cnt,
+                &mut collisions,
+            )
+        })?;
+        let count = (dev_cnt as usize).min(responses.len());
+        for (r, raw) in responses.iter_mut().zip(res.iter()).take(count) {
+            *r = FelicaPollRes::decode(raw);
+        }
+        Ok((count, collisions as usize))
+    }
+    /// Runs the collision resolution, fills `devices` and returns the number found.
+    pub fn collision_resolution(
+        comp_mode: rfalComplianceMode,
+        devices: &mut [NfcfDevice],
+    ) -> Result<usize> {
+        let mut dev_cnt = 0u8;
+        result(unsafe {
+            rfal_sys::rfalNfcfPollerCollisionResolution(
+                comp_mode,
+                devices.len().min(u8::MAX as usize) as u8,
+                devices.as_mut_ptr() as *mut rfalNfcfListenDevice,
+                &mut dev_cnt,
+            )
+        })?;
+        Ok(dev_cnt as usize)
+    }
+    pub fn new(device: &Device) -> Result<Self> {
+        if device.dev_type() != rfalNfcDevType::RFAL_NFC_LISTEN_TYPE_NFCF {
+            return Err(Error::Param);
+        }
+        Ok(Self::with_nfcid2(device.nfcf().nfcid2()))
+    }
+    pub fn with_nfcid2(nfcid2: [u8; FELICA_NFCID2_LEN]) -> Self {
+        Self { nfcid2 }
+    }
+    pub fn nfcid2(&self) -> [u8; FELICA_NFCID2_LEN] {
+        self.nfcid2
+    }
+    /// Check (Read Without Encryption) of the `blocks` of `service` into `buf`,
+    /// returns the number of bytes read.
+    pub fn check(&mut self, service: u16, blocks: &[u16], buf: &mut [u8]) -> Result<usize> {
+        if blocks.is_empty() || blocks.len() > FELICA_CHECK_MAX_BLOCKS {
+            return Err(Error::Param);
+        }
+        let len = blocks.len() * FELICA_BLOCK_LEN;
+        if buf.len() < len {
+            return Err(Error::NoMem);
+        }
+        let mut list = [block_list_elem(0); FELICA_CHECK_MAX_BLOCKS];
+        list.iter_mut()
+            .zip(blocks)
+            .for_each(|(e, &b)| *e = block_list_elem(b));
+        let mut service = service;
+        let param = serv_block_list(&mut service, &mut list[..blocks.len()]);
+        let mut rx = [0u8; CHECK_RES_HEADER_LEN + FELICA_CHECK_MAX_BLOCKS * FELICA_BLOCK_LEN];
+        let mut rcv_len = 0u16;
+        result(unsafe {
+            rfal_sys::rfalNfcfPollerCheck(
+                self.nfcid2.as_ptr(),
+                &param,
+                rx.as_mut_ptr(),
+                rx.len() as u16,
+                &mut rcv_len,
+            )
+        })?;
+        // the number of blocks is followed by the block data
+        let data = rx.get(1..rcv_len as usize).ok_or(Error::Proto)?;
+        if data.len() != len {
+            return Err(Error::Proto);
+        }
+        buf[..len].copy_from_slice(data);
+        Ok(len)
+    }
+    /// Update (Write Without Encryption) of the `blocks` of `service` with `data`,
+    /// 16 bytes per block.
+    pub fn update(&mut self, service: u16, blocks: &[u16], data: &[u8]) -> Result<()> {
+        if blocks.is_empty()
+            || blocks.len() > FELICA_UPDATE_MAX_BLOCKS
+            || data.len() != blocks.len() * FELICA_BLOCK_LEN
+        {
+            return Err(Error::Param);
+        }
+        let mut list = [block_list_elem(0); FELICA_UPDATE_MAX_BLOCKS];
+        list.iter_mut()
+            .zip(blocks)
+            .for_each(|(e, &b)| *e = block_list_elem(b));
+        let mut service = service;
+        let param = serv_block_list(&mut service, &mut list[..blocks.len()]);
+        let mut tx = [0u8; UPDATE_REQ_MAX_LEN];
+        let mut rx = [0u8; UPDATE_RES_LEN];
+        result(unsafe {
+            rfal_sys::rfalNfcfPollerUpdate(
+                self.nfcid2.as_ptr(),
+                &param,
+                tx.as_mut_ptr(),
+                tx.len() as u16,
+                data.as_ptr(),
+                rx.as_mut_ptr(),
+                rx.len() as u16,
+            )
+        })
+    }
+    /// Reads `count` consecutive blocks of `service` from `block` into `buf`.
+    pub fn read_blocks(
+        &mut self,
+        service: u16,
+        block: u16,
+        count: usize,
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        let mut blocks = [0u16; FELICA_CHECK_MAX_BLOCKS];
+        let blocks = consecutive(&mut blocks, block, count)?;
+        self.check(service, blocks, buf)
+    }
+    /// Writes `data` to consecutive blocks of `service` from `block`.
+    pub fn write_blocks(&mut self, service: u16, block: u16, data: &[u8]) -> Result<()> {
+        if !data.len().is_multiple_of(FELICA_BLOCK_LEN) {
+            return Err(Error::Param);
+        }
+        let mut blocks = [0u16; FELICA_UPDATE_MAX_BLOCKS];
+        let blocks = consecutive(&mut blocks, block, data.len() / FELICA_BLOCK_LEN)?;
+        self.update(service, blocks, data)
+    }
+}
+
+// 2 bytes element for block numbers up to 255, first service of the list
+fn block_list_elem(block: u16) -> rfalNfcfBlockListElem {
+    rfalNfcfBlockListElem {
+        conf: if block <= u8::MAX as u16 {
+            rfal_sys::RFAL_NFCF_BLOCKLISTELEM_LEN_BIT as u8
+        } else {
+            0
+        },
+        blockNum: block,
+    }
+}
+
+fn serv_block_list(
+    service: &mut u16,
+    list: &mut [rfalNfcfBlockListElem],
+) -> rfalNfcfServBlockListParam {
+    rfalNfcfServBlockListParam {
+        numServ: 1,
+        servList: service,
+        numBlock: list.len() as u8,
+        blockList: list.as_mut_ptr(),
+    }
+}
+
+fn consecutive(blocks: &mut [u16], first: u16, count: usize) -> Result<&[u16]> {
+    if count == 0 || count > blocks.len() || first as usize + count > u16::MAX as usize + 1 {
+        return Err(Error::Param);
+    }
+    for (i, b) in blocks[..count].iter_mut().enumerate() {
+        *b = first + i as u16;
+    }
+    Ok(&blocks[..count])
+}
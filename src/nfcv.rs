@@ -0,0 +1,481 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{result, Device, Error, Result};
+use rfal_sys::{rfalComplianceMode, rfalNfcDevType, rfalNfcvListenDevice, rfalNfcvNumSlots};
+
+pub const NFCV_UID_LEN: usize = rfal_sys::RFAL_NFCV_UID_LEN as usize;
+pub const NFCV_MAX_BLOCK_LEN: usize = rfal_sys::RFAL_NFCV_MAX_BLOCK_LEN as usize;
+
+const CMD_GET_MULTIPLE_BLOCK_SECURITY_STATUS: u8 = 0x2C;
+const CMD_EXTENDED_GET_MULTIPLE_BLOCK_SECURITY_STATUS: u8 = 0x3C;
+
+const INFO_DSFID: u8 = 0x01;
+const INFO_AFI: u8 = 0x02;
+const INFO_MEMORY_SIZE: u8 = 0x04;
+const INFO_IC_REFERENCE: u8 = 0x08;
+
+// response flags byte, followed by the block data
+const RES_FLAG_LEN: usize = 1;
+const WRITE_MULTIPLE_BUF_LEN: usize = 256;
+
+/// NFC-V device answering an inventory.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct NfcvDevice(pub(crate) rfalNfcvListenDevice);
+
+impl Default for NfcvDevice {
+    fn default() -> Self {
+        // allocate default values manually, thanks bingen to not deriving Default trait...
+        Self(rfalNfcvListenDevice {
+            InvRes: rfal_sys::rfalNfcvInventoryRes {
+                RES_FLAG: 0,
+                DSFID: 0,
+                UID: [0; NFCV_UID_LEN],
+                crc: [0; 2],
+            },
+            isSleep: false,
+        })
+    }
+}
+
+impl NfcvDevice {
+    /// UID as sent over the air, least significant byte first.
+    pub fn uid(&self) -> [u8; NFCV_UID_LEN] {
+        self.0.InvRes.UID
+    }
+    pub fn dsfid(&self) -> u8 {
+        self.0.InvRes.DSFID
+    }
+    pub fn is_sleeping(&self) -> bool {
+        self.0.isSleep
+    }
+}
+
+/// How requests designate the tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NfcvAddressing {
+    /// Any tag in the field answers.
+    NonAddressed,
+    /// Requests carry the UID.
+    Addressed,
+    /// Only the tag in selected state answers, see [`NfcV::select`].
+    Selected,
+}
+
+/// GET SYSTEM INFO response, fields not reported by the tag are `None`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NfcvSystemInfo {
+    pub uid: [u8; NFCV_UID_LEN],
+    pub dsfid: Option<u8>,
+    pub afi: Option<u8>,
+    pub block_count: Option<u32>,
+    pub block_len: Option<u8>,
+    pub ic_reference: Option<u8>,
+}
+
+impl NfcvSystemInfo {
+    fn decode(data: &[u8], extended: bool) -> Result<Self> {
+        let mut it = data.iter().copied();
+        let mut next = || it.next().ok_or(Error::Proto);
+        let info_flags = next()?;
+        let mut uid = [0u8; NFCV_UID_LEN];
+        for b in uid.iter_mut() {
+            *b = next()?;
+        }
+        let dsfid = (info_flags & INFO_DSFID != 0).then(&mut next).transpose()?;
+        let afi = (info_flags & INFO_AFI != 0).then(&mut next).transpose()?;
+        let (block_count, block_len) = if info_flags & INFO_MEMORY_SIZE != 0 {
+            let count = if extended {
+                u16::from_le_bytes([next()?, next()?]) as u32
+            } else {
+                next()? as u32
+            };
+            (Some(count + 1), Some((next()? & 0x1F) + 1))
+        } else {
+            (None, None)
+        };
+        let ic_reference = (info_flags & INFO_IC_REFERENCE != 0)
+            .then(&mut next)
+            .transpose()?;
+        Ok(Self {
+            uid,
+            dsfid,
+            afi,
+            block_count,
+            block_len,
+            ic_reference,
+        })
+    }
+}
+
+/// ISO 15693 poller commands for one NFC-V tag.
+pub struct NfcV {
+    uid: [u8; NFCV_UID_LEN],
+    addressing: NfcvAddressing,
+}
+
+impl NfcV {
+    /// Sets the RF configuration for NFC-V polling, not needed after a discovery.
+    pub fn initialize() -> Result<()> {
+        result(unsafe { rfal_sys::rfalNfcvPollerInitialize() })
+    }
+    /// Runs a 16 slots inventory with collision resolution, fills `devices`
+    /// and returns the number of devices found.
+    pub fn inventory(devices: &mut [NfcvDevice]) -> Result<usize> {
+        let mut dev_cnt = 0u8;
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerCollisionResolution(
+                rfalComplianceMode::RFAL_COMPLIANCE_MODE_NFC,
+                devices.len().min(u8::MAX as usize) as u8,
+                devices.as_mut_ptr() as *mut rfalNfcvListenDevice,
+                &mut dev_cnt,
+            )
+        })?;
+        Ok(dev_cnt as usize)
+    }
+    /// Single slot inventory, fails with `Timeout` when no tag answers.
+    pub fn inventory_single() -> Result<NfcvDevice> {
+        let mut device = NfcvDevice::default();
+        let mut rcv_len = 0u16;
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerInventory(
+                rfalNfcvNumSlots::RFAL_NFCV_NUM_SLOTS_1,
+                0,
+                core::ptr::null(),
+                &mut device.0.InvRes,
+                &mut rcv_len,
+            )
+        })?;
+        Ok(device)
+    }
+    /// Handle on the activated NFC-V `device`, in addressed mode.
+    pub fn new(device: &Device) -> Result<Self> {
+        if device.dev_type() != rfalNfcDevType::RFAL_NFC_LISTEN_TYPE_NFCV {
+            return Err(Error::Param);
+        }
+        Ok(Self::with_uid(device.nfcv().uid()))
+    }
+    pub fn with_uid(uid: [u8; NFCV_UID_LEN]) -> Self {
+        Self {
+            uid,
+            addressing: NfcvAddressing::Addressed,
+        }
+    }
+    pub fn uid(&self) -> [u8; NFCV_UID_LEN] {
+        self.uid
+    }
+    pub fn addressing(&self) -> NfcvAddressing {
+        self.addressing
+    }
+    pub fn set_addressing(&mut self, addressing: NfcvAddressing) {
+        self.addressing = addressing;
+    }
+    /// Puts the tag in selected state and switches to selected mode.
+    pub fn select(&mut self) -> Result<()> {
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerSelect(
+                rfal_sys::RFAL_NFCV_REQ_FLAG_DEFAULT as u8,
+                self.uid.as_ptr(),
+            )
+        })?;
+        self.addressing = NfcvAddressing::Selected;
+        Ok(())
+    }
+    /// Puts the tag in quiet state, it only answers addressed requests afterwards.
+    pub fn stay_quiet(&mut self) -> Result<()> {
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerSleep(
+                rfal_sys::RFAL_NFCV_REQ_FLAG_DEFAULT as u8,
+                self.uid.as_ptr(),
+            )
+        })?;
+        self.addressing = NfcvAddressing::Addressed;
+        Ok(())
+    }
+    /// Reads `block` into `buf` and returns the block length.
+    pub fn read_single_block(&mut self, block: u8, buf: &mut [u8]) -> Result<usize> {
+        let mut rx = [0u8; RES_FLAG_LEN + NFCV_MAX_BLOCK_LEN];
+        let mut rcv_len = 0u16;
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerReadSingleBlock(
+                self.flags(),
+                self.uid_ptr(),
+                block,
+                rx.as_mut_ptr(),
+                rx.len() as u16,
+                &mut rcv_len,
+            )
+        })?;
+        copy_response(&rx[..rcv_len as usize], buf)
+    }
+    pub fn extended_read_single_block(&mut self, block: u16, buf: &mut [u8]) -> Result<usize> {
+        let mut rx = [0u8; RES_FLAG_LEN + NFCV_MAX_BLOCK_LEN];
+        let mut rcv_len = 0u16;
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerExtendedReadSingleBlock(
+                self.flags(),
+                self.uid_ptr(),
+                block,
+                rx.as_mut_ptr(),
+                rx.len() as u16,
+                &mut rcv_len,
+            )
+        })?;
+        copy_response(&rx[..rcv_len as usize], buf)
+    }
+    pub fn write_single_block(&mut self, block: u8, data: &[u8]) -> Result<()> {
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerWriteSingleBlock(
+                self.flags(),
+                self.uid_ptr(),
+                block,
+                data.as_ptr(),
+                block_len(data)?,
+            )
+        })
+    }
+    pub fn extended_write_single_block(&mut self, block: u16, data: &[u8]) -> Result<()> {
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerExtendedWriteSingleBlock(
+                self.flags(),
+                self.uid_ptr(),
+                block,
+                data.as_ptr(),
+                block_len(data)?,
+            )
+        })
+    }
+    /// Reads `count` blocks (1 to 256) from `first`, `buf` needs one spare byte
+    /// for the response flags. Returns the data length.
+    pub fn read_multiple_blocks(&mut self, first: u8, count: u16, buf: &mut [u8]) -> Result<usize> {
+        let count_minus_1 = multiple_block_count(first, count)?;
+        let mut rcv_len = 0u16;
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerReadMultipleBlocks(
+                self.flags(),
+                self.uid_ptr(),
+                first,
+                count_minus_1,
+                buf.as_mut_ptr(),
+                buf.len().min(u16::MAX as usize) as u16,
+                &mut rcv_len,
+            )
+        })?;
+        strip_response(buf, rcv_len as usize)
+    }
+    pub fn extended_read_multiple_blocks(
+        &mut self,
+        first: u16,
+        count: u16,
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        if count == 0 {
+            return Err(Error::Param);
+        }
+        let mut rcv_len = 0u16;
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerExtendedReadMultipleBlocks(
+                self.flags(),
+                self.uid_ptr(),
+                first,
+                count - 1,
+                buf.as_mut_ptr(),
+                buf.len().min(u16::MAX as usize) as u16,
+                &mut rcv_len,
+            )
+        })?;
+        strip_response(buf, rcv_len as usize)
+    }
+    /// Writes `count` blocks (1 to 256) from `first`, `data` holds the blocks back
+    /// to back. RFAL takes at most 255 blocks per command, 256 blocks are written
+    /// by two commands.
+    pub fn write_multiple_blocks(&mut self, first: u8, count: u16, data: &[u8]) -> Result<()> {
+        multiple_block_count(first, count)?;
+        let block_len = multiple_block_len(data, count as usize)?;
+        let mut tx = [0u8; WRITE_MULTIPLE_BUF_LEN];
+        for (i, blocks) in data
+            .chunks(block_len as usize * u8::MAX as usize)
+            .enumerate()
+        {
+            result(unsafe {
+                rfal_sys::rfalNfcvPollerWriteMultipleBlocks(
+                    self.flags(),
+                    self.uid_ptr(),
+                    first + (i * u8::MAX as usize) as u8,
+                    (blocks.len() / block_len as usize) as u8,
+                    tx.as_mut_ptr(),
+                    tx.len() as u16,
+                    block_len,
+                    blocks.as_ptr(),
+                    blocks.len() as u16,
+                )
+            })?;
+        }
+        Ok(())
+    }
+    pub fn extended_write_multiple_blocks(
+        &mut self,
+        first: u16,
+        count: u16,
+        data: &[u8],
+    ) -> Result<()> {
+        let block_len = multiple_block_len(data, count as usize)?;
+        let mut tx = [0u8; WRITE_MULTIPLE_BUF_LEN];
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerExtendedWriteMultipleBlocks(
+                self.flags(),
+                self.uid_ptr(),
+                first,
+                count,
+                tx.as_mut_ptr(),
+                tx.len() as u16,
+                block_len,
+                data.as_ptr(),
+                data.len() as u16,
+            )
+        })
+    }
+    /// Permanently write protects `block`.
+    pub fn lock_block(&mut self, block: u8) -> Result<()> {
+        result(unsafe { rfal_sys::rfalNfcvPollerLockBlock(self.flags(), self.uid_ptr(), block) })
+    }
+    pub fn extended_lock_block(&mut self, block: u16) -> Result<()> {
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerExtendedLockSingleBlock(self.flags(), self.uid_ptr(), block)
+        })
+    }
+    pub fn system_info(&mut self) -> Result<NfcvSystemInfo> {
+        let mut rx = [0u8; 32];
+        let mut rcv_len = 0u16;
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerGetSystemInformation(
+                self.flags(),
+                self.uid_ptr(),
+                rx.as_mut_ptr(),
+                rx.len() as u16,
+                &mut rcv_len,
+            )
+        })?;
+        let info = rx.get(RES_FLAG_LEN..rcv_len as usize).ok_or(Error::Proto)?;
+        NfcvSystemInfo::decode(info, false)
+    }
+    /// EXTENDED GET SYSTEM INFO, needed by tags with more than 256 blocks.
+    pub fn extended_system_info(&mut self) -> Result<NfcvSystemInfo> {
+        let mut rx = [0u8; 32];
+        let mut rcv_len = 0u16;
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerExtendedGetSystemInformation(
+                self.flags(),
+                self.uid_ptr(),
+                INFO_DSFID | INFO_AFI | INFO_MEMORY_SIZE | INFO_IC_REFERENCE,
+                rx.as_mut_ptr(),
+                rx.len() as u16,
+                &mut rcv_len,
+            )
+        })?;
+        let info = rx.get(RES_FLAG_LEN..rcv_len as usize).ok_or(Error::Proto)?;
+        NfcvSystemInfo::decode(info, true)
+    }
+    /// Reads the security status of `count` blocks (1 to 256) from `first` into
+    /// `buf`, one byte per block, bit 0 set when the block is locked.
+    pub fn block_security_status(
+        &mut self,
+        first: u8,
+        count: u16,
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        let count_minus_1 = multiple_block_count(first, count)?;
+        self.transceive(
+            CMD_GET_MULTIPLE_BLOCK_SECURITY_STATUS,
+            &[first, count_minus_1],
+            buf,
+        )
+    }
+    pub fn extended_block_security_status(
+        &mut self,
+        first: u16,
+        count: u16,
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        if count == 0 {
+            return Err(Error::Param);
+        }
+        let [first_lo, first_hi] = first.to_le_bytes();
+        let [count_lo, count_hi] = (count - 1).to_le_bytes();
+        self.transceive(
+            CMD_EXTENDED_GET_MULTIPLE_BLOCK_SECURITY_STATUS,
+            &[first_lo, first_hi, count_lo, count_hi],
+            buf,
+        )
+    }
+    fn transceive(&mut self, cmd: u8, data: &[u8], buf: &mut [u8]) -> Result<usize> {
+        let mut rcv_len = 0u16;
+        result(unsafe {
+            rfal_sys::rfalNfcvPollerTransceiveReq(
+                cmd,
+                self.flags(),
+                rfal_sys::RFAL_NFCV_PARAM_SKIP as u8,
+                self.uid_ptr(),
+                data.as_ptr(),
+                data.len() as u16,
+                buf.as_mut_ptr(),
+                buf.len().min(u16::MAX as usize) as u16,
+                &mut rcv_len,
+            )
+        })?;
+        strip_response(buf, rcv_len as usize)
+    }
+    pub(crate) fn flags(&self) -> u8 {
+        let flags = rfal_sys::RFAL_NFCV_REQ_FLAG_DEFAULT as u8;
+        match self.addressing {
+            NfcvAddressing::Selected => flags | rfal_sys::RFAL_NFCV_REQ_FLAG_SELECT as u8,
+            _ => flags,
+        }
+    }
+    // the address flag is set by RFAL when a UID is given
+    pub(crate) fn uid_ptr(&self) -> *const u8 {
+        match self.addressing {
+            NfcvAddressing::Addressed => self.uid.as_ptr(),
+            _ => core::ptr::null(),
+        }
+    }
+}
+
+fn block_len(data: &[u8]) -> Result<u8> {
+    if data.is_empty() || data.len() > NFCV_MAX_BLOCK_LEN {
+        return Err(Error::Param);
+    }
+    Ok(data.len() as u8)
+}
+
+// the frame holds the number of blocks minus one, up to the last block number
+fn multiple_block_count(first: u8, count: u16) -> Result<u8> {
+    if count == 0 || first as u16 + count > 256 {
+        return Err(Error::Param);
+    }
+    Ok((count - 1) as u8)
+}
+
+fn multiple_block_len(data: &[u8], count: usize) -> Result<u8> {
+    if count == 0 || !data.len().is_multiple_of(count) {
+        return Err(Error::Param);
+    }
+    block_len(&data[..data.len() / count])
+}
+
+fn copy_response(rx: &[u8], buf: &mut [u8]) -> Result<usize> {
+    let data = rx.get(RES_FLAG_LEN..).ok_or(Error::Proto)?;
+    buf.get_mut(..data.len())
+        .ok_or(Error::NoMem)?
+        .copy_from_slice(data);
+    Ok(data.len())
+}
+
+fn strip_response(buf: &mut [u8], rcv_len: usize) -> Result<usize> {
+    if rcv_len < RES_FLAG_LEN {
+        return Err(Error::Proto);
+    }
+    buf.copy_within(RES_FLAG_LEN..rcv_len, 0);
+    Ok(rcv_len - RES_FLAG_LEN)
+}
@@ -0,0 +1,484 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{result, us_to_1fc, Device, Error, Result, T2t, T2T_PAGE_LEN};
+
+const CMD_GET_VERSION: u8 = 0x60;
+const CMD_FAST_READ: u8 = 0x3A;
+const CMD_READ_CNT: u8 = 0x39;
+const CMD_READ_SIG: u8 = 0x3C;
+const CMD_PWD_AUTH: u8 = 0x1B;
+
+const ACK: u8 = 0x0A;
+const ACK_MASK: u8 = 0x0F;
+
+// RFAL_TXRX_FLAGS_DEFAULT, all the default flags are 0
+const TXRX_FLAGS_DEFAULT: u32 = 0;
+const FWT_US: u32 = 5000;
+
+pub const NTAG_SIGNATURE_LEN: usize = 32;
+
+/// NXP public key for the originality signature of NTAG21x and Ultralight EV1.
+pub const NXP_ORIGINALITY_KEY: [u8; 33] = [
+    0x04, 0x49, 0x4E, 0x1A, 0x38, 0x6D, 0x3D, 0x3C, 0xFE, 0x3D, 0xC1, 0x0E, 0x5D, 0xE6, 0x8A, 0x49,
+    0x9B, 0x1C, 0x20, 0x2D, 0xB5, 0xB1, 0x32, 0x39, 0x3E, 0x89, 0xED, 0x19, 0xFE, 0x5B, 0xE8, 0xBC,
+    0x61,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NtagProduct {
+    Ntag210,
+    Ntag212,
+    Ntag213,
+    Ntag215,
+    Ntag216,
+    UltralightEv1Mf0ul11,
+    UltralightEv1Mf0ul21,
+}
+
+impl NtagProduct {
+    /// Page holding CFG0, followed by CFG1, PWD and PACK.
+    pub fn config_page(&self) -> u8 {
+        match self {
+            NtagProduct::Ntag210 | NtagProduct::UltralightEv1Mf0ul11 => 0x10,
+            NtagProduct::Ntag212 | NtagProduct::UltralightEv1Mf0ul21 => 0x25,
+            NtagProduct::Ntag213 => 0x29,
+            NtagProduct::Ntag215 => 0x83,
+            NtagProduct::Ntag216 => 0xE3,
+        }
+    }
+    pub fn user_memory_len(&self) -> usize {
+        match self {
+            NtagProduct::Ntag210 | NtagProduct::UltralightEv1Mf0ul11 => 48,
+            NtagProduct::Ntag212 | NtagProduct::UltralightEv1Mf0ul21 => 128,
+            NtagProduct::Ntag213 => 144,
+            NtagProduct::Ntag215 => 504,
+            NtagProduct::Ntag216 => 888,
+        }
+    }
+    /// Counters readable with READ_CNT.
+    pub fn counters(&self) -> &'static [u8] {
+        match self {
+            NtagProduct::Ntag210 | NtagProduct::Ntag212 => &[],
+            NtagProduct::Ntag213 | NtagProduct::Ntag215 | NtagProduct::Ntag216 => &[2],
+            NtagProduct::UltralightEv1Mf0ul11 | NtagProduct::UltralightEv1Mf0ul21 => &[0, 1, 2],
+        }
+    }
+}
+
+/// GET_VERSION response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NtagVersion {
+    pub vendor_id: u8,
+    pub product_type: u8,
+    pub product_subtype: u8,
+    pub major_version: u8,
+    pub minor_version: u8,
+    pub storage_size: u8,
+    pub protocol_type: u8,
+}
+
+impl NtagVersion {
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let [_, vendor_id, product_type, product_subtype, major_version, minor_version, storage_size, protocol_type] =
+            *data
+        else {
+            return Err(Error::Proto);
+        };
+        Ok(Self {
+            vendor_id,
+            product_type,
+            product_subtype,
+            major_version,
+            minor_version,
+            storage_size,
+            protocol_type,
+        })
+    }
+    pub fn product(&self) -> Option<NtagProduct> {
+        // NXP
+        if self.vendor_id != 0x04 {
+            return None;
+        }
+        match (self.product_type, self.product_subtype, self.storage_size) {
+            (0x04, 0x01, 0x0B) => Some(NtagProduct::Ntag210),
+            (0x04, 0x01, 0x0E) => Some(NtagProduct::Ntag212),
+            (0x04, 0x02, 0x0F) => Some(NtagProduct::Ntag213),
+            (0x04, 0x02, 0x11) => Some(NtagProduct::Ntag215),
+            (0x04, 0x02, 0x13) => Some(NtagProduct::Ntag216),
+            (0x03, _, 0x0B) => Some(NtagProduct::UltralightEv1Mf0ul11),
+            (0x03, _, 0x0E) => Some(NtagProduct::UltralightEv1Mf0ul21),
+            _ => None,
+        }
+    }
+}
+
+/// ACCESS configuration byte, in CFG1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NtagAccess {
+    /// Password also protects reads from AUTH0, not only writes.
+    pub read_protected: bool,
+    /// Permanently locks the configuration pages.
+    pub config_locked: bool,
+    pub nfc_counter: bool,
+    pub nfc_counter_protected: bool,
+    /// Number of failed PWD_AUTH before the password is locked, 0 disables the limit.
+    pub auth_limit: u8,
+}
+
+impl From<u8> for NtagAccess {
+    fn from(value: u8) -> Self {
+        Self {
+            read_protected: value & 0x80 != 0,
+            config_locked: value & 0x40 != 0,
+            nfc_counter: value & 0x10 != 0,
+            nfc_counter_protected: value & 0x08 != 0,
+            auth_limit: value & 0x07,
+        }
+    }
+}
+
+impl From<NtagAccess> for u8 {
+    fn from(value: NtagAccess) -> Self {
+        (value.read_protected as u8) << 7
+            | (value.config_locked as u8) << 6
+            | (value.nfc_counter as u8) << 4
+            | (value.nfc_counter_protected as u8) << 3
+            | value.auth_limit & 0x07
+    }
+}
+
+/// NTAG21x and Ultralight EV1 commands, for an activated NFC-A T2T device.
+pub struct Ntag<'d> {
+    t2t: T2t<'d>,
+    version: NtagVersion,
+    product: NtagProduct,
+}
+
+impl<'d> Ntag<'d> {
+    /// Identifies the tag with GET_VERSION, fails with `NotSupp` for other T2T.
+    pub fn new(device: &'d Device) -> Result<Self> {
+        let t2t = T2t::new(device)?;
+        let version = Self::get_version()?;
+        let product = version.product().ok_or(Error::NotSupp)?;
+        Ok(Self {
+            t2t,
+            version,
+            product,
+        })
+    }
+    pub fn get_version() -> Result<NtagVersion> {
+        let mut rx = [0u8; 8];
+        let len = transceive(&mut [CMD_GET_VERSION], &mut rx, FWT_US)?;
+        NtagVersion::decode(&rx[..len])
+    }
+    pub fn version(&self) -> &NtagVersion {
+        &self.version
+    }
+    pub fn product(&self) -> NtagProduct {
+        self.product
+    }
+    /// Generic T2T access, READ and WRITE.
+    pub fn t2t(&mut self) -> &mut T2t<'d> {
+        &mut self.t2t
+    }
+    /// Reads pages `start` to `end` included into `buf`.
+    pub fn fast_read(&mut self, start: u8, end: u8, buf: &mut [u8]) -> Result<usize> {
+        if end < start {
+            return Err(Error::Param);
+        }
+        let len = (end - start + 1) as usize * T2T_PAGE_LEN;
+        if buf.len() < len {
+            return Err(Error::NoMem);
+        }
+        let rcv_len = transceive(&mut [CMD_FAST_READ, start, end], &mut buf[..len], FWT_US)?;
+        if rcv_len != len {
+            return Err(Error::Proto);
+        }
+        Ok(len)
+    }
+    /// Reads the 24 bits one-way `counter`.
+    pub fn read_counter(&mut self, counter: u8) -> Result<u32> {
+        if !self.product.counters().contains(&counter) {
+            return Err(Error::Param);
+        }
+        let mut rx = [0u8; 3];
+        if transceive(&mut [CMD_READ_CNT, counter], &mut rx, FWT_US)? != rx.len() {
+            return Err(Error::Proto);
+        }
+        Ok(u32::from_le_bytes([rx[0], rx[1], rx[2], 0]))
+    }
+    pub fn read_signature(&mut self) -> Result<[u8; NTAG_SIGNATURE_LEN]> {
+        let mut signature = [0u8; NTAG_SIGNATURE_LEN];
+        if transceive(&mut [CMD_READ_SIG, 0x00], &mut signature, FWT_US)? != signature.len() {
+            return Err(Error::Proto);
+        }
+        Ok(signature)
+    }
+    /// Reads the originality signature and verifies it against the UID with [`NXP_ORIGINALITY_KEY`].
+    pub fn verify_originality(&mut self) -> Result<bool> {
+        let signature = self.read_signature()?;
+        let uid = self.t2t.device().id().ok_or(Error::WrongState)?;
+        Ok(verify_signature(&NXP_ORIGINALITY_KEY, uid, &signature))
+    }
+    /// Authenticates with `password` and returns the PACK answered by the tag.
+    pub fn pwd_auth(&mut self, password: [u8; 4]) -> Result<[u8; 2]> {
+        let mut tx = [CMD_PWD_AUTH, 0, 0, 0, 0];
+        tx[1..].copy_from_slice(&password);
+        let mut pack = [0u8; 2];
+        if transceive(&mut tx, &mut pack, FWT_US)? != pack.len() {
+            return Err(Error::Proto);
+        }
+        Ok(pack)
+    }
+    /// First page protected by the password, protection is disabled beyond the last page.
+    pub fn auth0(&mut self) -> Result<u8> {
+        Ok(self.config()?[3])
+    }
+    pub fn set_auth0(&mut self, page: u8) -> Result<()> {
+        let mut cfg0 = [0u8; T2T_PAGE_LEN];
+        cfg0.copy_from_slice(&self.config()?[..T2T_PAGE_LEN]);
+        cfg0[3] = page;
+        self.t2t.write_page(self.product.config_page(), cfg0)
+    }
+    pub fn access(&mut self) -> Result<NtagAccess> {
+        Ok(self.config()?[T2T_PAGE_LEN].into())
+    }
+    pub fn set_access(&mut self, access: NtagAccess) -> Result<()> {
+        let mut cfg1 = [0u8; T2T_PAGE_LEN];
+        cfg1.copy_from_slice(&self.config()?[T2T_PAGE_LEN..2 * T2T_PAGE_LEN]);
+        cfg1[0] = access.into();
+        self.t2t.write_page(self.product.config_page() + 1, cfg1)
+    }
+    /// Writes PWD and PACK, they always read back as zeros.
+    pub fn set_password(&mut self, password: [u8; 4], pack: [u8; 2]) -> Result<()> {
+        let page = self.product.config_page();
+        self.t2t.write_page(page + 2, password)?;
+        self.t2t.write_page(page + 3, [pack[0], pack[1], 0, 0])
+    }
+    /// Sets the password and access, then enables the protection from `auth0`.
+    pub fn protect(
+        &mut self,
+        password: [u8; 4],
+        pack: [u8; 2],
+        access: NtagAccess,
+        auth0: u8,
+    ) -> Result<()> {
+        self.set_password(password, pack)?;
+        self.set_access(access)?;
+        self.set_auth0(auth0)
+    }
+    // CFG0, CFG1, PWD and PACK pages
+    fn config(&mut self) -> Result<[u8; 4 * T2T_PAGE_LEN]> {
+        self.t2t.read_pages(self.product.config_page())
+    }
+}
+
+fn transceive(tx: &mut [u8], rx: &mut [u8], fwt_us: u32) -> Result<usize> {
+    let mut rcv_len = 0u16;
+    match result(unsafe {
+        rfal_sys::rfalTransceiveBlockingTxRx(
+            tx.as_mut_ptr(),
+            tx.len() as u16,
+            rx.as_mut_ptr(),
+            rx.len() as u16,
+            &mut rcv_len,
+            TXRX_FLAGS_DEFAULT,
+            us_to_1fc(fwt_us),
+        )
+    }) {
+        Ok(()) => Ok(rcv_len as usize),
+        // 4 bits ACK/NAK, none of these commands is answered by an ACK
+        Err(Error::IncompleteByte(_)) if rcv_len == 1 && rx[0] & ACK_MASK != ACK => {
+            Err(Error::Proto)
+        }
+        Err(Error::IncompleteByte(_)) if rcv_len == 1 => Ok(0),
+        Err(e) => Err(e),
+    }
+}
+
+/// Verifies an originality `signature` (r and s, big endian) of `uid` with the
+/// uncompressed secp128r1 `public_key`.
+pub fn verify_signature(public_key: &[u8; 33], uid: &[u8], signature: &[u8; 32]) -> bool {
+    secp128r1::verify(public_key, uid, signature)
+}
+
+mod secp128r1 {
+    const P: Modulus = Modulus(0xFFFFFFFD_FFFFFFFF_FFFFFFFF_FFFFFFFF);
+    const N: Modulus = Modulus(0xFFFFFFFE_00000000_75A30D1B_9038A115);
+    const A: u128 = 0xFFFFFFFD_FFFFFFFF_FFFFFFFF_FFFFFFFC;
+    const B: u128 = 0xE87579C1_1079F43D_D824993C_2CEE5ED3;
+    const G: Point = Some((
+        0x161FF752_8B899B2D_0C28607C_A52C5B86,
+        0xCF5AC839_5BAFEB13_C02DA292_DDED7A83,
+    ));
+
+    // affine coordinates, None is the point at infinity
+    type Point = Option<(u128, u128)>;
+
+    #[derive(Clone, Copy)]
+    struct Modulus(u128);
+
+    impl Modulus {
+        fn add(self, a: u128, b: u128) -> u128 {
+            let (sum, carry) = a.overflowing_add(b);
+            if carry || sum >= self.0 {
+                sum.wrapping_sub(self.0)
+            } else {
+                sum
+            }
+        }
+        fn sub(self, a: u128, b: u128) -> u128 {
+            if a >= b {
+                a - b
+            } else {
+                a.wrapping_sub(b).wrapping_add(self.0)
+            }
+        }
+        fn mul(self, a: u128, b: u128) -> u128 {
+            let (mut hi, mut lo) = mul_wide(a, b);
+            // fold the high half with 2^128 = 2^128 - m (mod m), m is close to 2^128
+            let c = self.0.wrapping_neg();
+            while hi != 0 {
+                let (h, l) = mul_wide(hi, c);
+                let (l, carry) = l.overflowing_add(lo);
+                hi = h + carry as u128;
+                lo = l;
+            }
+            if lo >= self.0 {
+                lo - self.0
+            } else {
+                lo
+            }
+        }
+        fn pow(self, a: u128, mut e: u128) -> u128 {
+            let mut base = a;
+            let mut acc = 1;
+            while e != 0 {
+                if e & 1 != 0 {
+                    acc = self.mul(acc, base);
+                }
+                base = self.mul(base, base);
+                e >>= 1;
+            }
+            acc
+        }
+        // m is prime
+        fn inv(self, a: u128) -> u128 {
+            self.pow(a, self.0 - 2)
+        }
+    }
+
+    fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+        let (a0, a1) = (a as u64 as u128, a >> 64);
+        let (b0, b1) = (b as u64 as u128, b >> 64);
+        let ll = a0 * b0;
+        let lh = a0 * b1;
+        let hl = a1 * b0;
+        let hh = a1 * b1;
+        let mid = (ll >> 64) + (lh as u64 as u128) + (hl as u64 as u128);
+        let lo = (ll as u64 as u128) | (mid << 64);
+        let hi = hh + (lh >> 64) + (hl >> 64) + (mid >> 64);
+        (hi, lo)
+    }
+
+    fn double(p: Point) -> Point {
+        let (x, y) = p?;
+        if y == 0 {
+            return None;
+        }
+        let num = P.add(P.mul(3, P.mul(x, x)), A);
+        let l = P.mul(num, P.inv(P.add(y, y)));
+        let x3 = P.sub(P.mul(l, l), P.add(x, x));
+        Some((x3, P.sub(P.mul(l, P.sub(x, x3)), y)))
+    }
+
+    fn add(p: Point, q: Point) -> Point {
+        let Some((x1, y1)) = p else { return q };
+        let Some((x2, y2)) = q else { return p };
+        if x1 == x2 {
+            return if y1 == y2 { double(p) } else { None };
+        }
+        let l = P.mul(P.sub(y2, y1), P.inv(P.sub(x2, x1)));
+        let x3 = P.sub(P.sub(P.mul(l, l), x1), x2);
+        Some((x3, P.sub(P.mul(l, P.sub(x1, x3)), y1)))
+    }
+
+    // u1 * G + u2 * q, interleaved
+    fn mul_add(u1: u128, u2: u128, q: Point) -> Point {
+        let gq = add(G, q);
+        let mut r = None;
+        for i in (0..128).rev() {
+            r = double(r);
+            r = match ((u1 >> i) & 1, (u2 >> i) & 1) {
+                (1, 1) => add(r, gq),
+                (1, 0) => add(r, G),
+                (0, 1) => add(r, q),
+                _ => r,
+            };
+        }
+        r
+    }
+
+    fn be(bytes: &[u8]) -> u128 {
+        bytes.iter().fold(0, |acc, &b| acc << 8 | b as u128)
+    }
+
+    fn on_curve(x: u128, y: u128) -> bool {
+        let rhs = P.add(P.add(P.mul(P.mul(x, x), x), P.mul(A, x)), B);
+        x < P.0 && y < P.0 && P.mul(y, y) == rhs
+    }
+
+    pub(super) fn verify(public_key: &[u8; 33], msg: &[u8], signature: &[u8; 32]) -> bool {
+        if public_key[0] != 0x04 || msg.len() > 16 {
+            return false;
+        }
+        let (qx, qy) = (be(&public_key[1..17]), be(&public_key[17..]));
+        if !on_curve(qx, qy) {
+            return false;
+        }
+        let (r, s) = (be(&signature[..16]), be(&signature[16..]));
+        if r == 0 || r >= N.0 || s == 0 || s >= N.0 {
+            return false;
+        }
+        let e = be(msg) % N.0;
+        let w = N.inv(s);
+        match mul_add(N.mul(e, w), N.mul(r, w), Some((qx, qy))) {
+            Some((x, _)) => x % N.0 == r,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::verify_signature;
+
+    // secp128r1 key pair and signature of the UID made with OpenSSL
+    const PUBLIC_KEY: [u8; 33] = [
+        0x04, 0x29, 0x4E, 0xF5, 0xE7, 0x8B, 0x65, 0xB0, 0xF7, 0xEA, 0x6A, 0x67, 0xA6, 0x12, 0x0B,
+        0x50, 0x86, 0x86, 0x39, 0x29, 0xA3, 0xF3, 0x52, 0xC3, 0x02, 0xF8, 0x06, 0x3A, 0xC3, 0x94,
+        0x87, 0xF6, 0xF6,
+    ];
+    const UID: [u8; 7] = [0x04, 0x2F, 0x3C, 0x9A, 0x61, 0x52, 0x80];
+    const SIGNATURE: [u8; 32] = [
+        0x86, 0x3B, 0xDF, 0xEB, 0x80, 0xC4, 0x89, 0xDC, 0x4C, 0x82, 0xF9, 0x2C, 0xDF, 0x5F, 0x77,
+        0x6C, 0x49, 0x7D, 0xC9, 0x4F, 0x1C, 0x37, 0x31, 0x47, 0x86, 0xA5, 0xAD, 0xDA, 0x2C, 0xF5,
+        0xD8, 0x8A,
+    ];
+
+    #[test]
+    fn verify_known_signature() {
+        assert!(verify_signature(&PUBLIC_KEY, &UID, &SIGNATURE));
+    }
+
+    #[test]
+    fn reject_altered_signature() {
+        let mut uid = UID;
+        uid[6] ^= 0x01;
+        assert!(!verify_signature(&PUBLIC_KEY, &uid, &SIGNATURE));
+        let mut signature = SIGNATURE;
+        signature[31] ^= 0x01;
+        assert!(!verify_signature(&PUBLIC_KEY, &UID, &signature));
+    }
+}
@@ -0,0 +1,222 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::ticks::{deadline, expired, now};
+use crate::{rfalNfcState, Discover, Nfc, Result, WakeUp, WakeUpConfig};
+
+// set from the IRQ_OUT interrupt while the reader is in wake-up mode
+static TAG_APPROACHED: AtomicBool = AtomicBool::new(false);
+
+/// Power manager configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PowerConfig {
+    pub wakeup: WakeUpConfig,
+    /// Time given to the discovery after a wake-up before it counts as a false one.
+    pub discovery_timeout_ms: u32,
+    /// Idle time after a false wake-up, doubled on each consecutive one.
+    pub backoff_min_ms: u32,
+    pub backoff_max_ms: u32,
+    /// Only polls the reader once [`PowerManager::on_irq`] signaled a wake-up,
+    /// otherwise the wake-up status is polled over SPI.
+    pub irq_driven: bool,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            wakeup: WakeUpConfig::default(),
+            discovery_timeout_ms: 1000,
+            backoff_min_ms: 100,
+            backoff_max_ms: 10_000,
+            irq_driven: false,
+        }
+    }
+}
+
+/// Where the power manager is in its cycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerState {
+    Stopped,
+    /// Wake-up mode, waiting for a tag to approach.
+    Sleeping,
+    /// RF field on, running the discovery.
+    Discovering,
+    /// A tag was found, waiting for [`PowerManager::release`].
+    Active,
+    /// Waiting for the backoff to expire before sleeping again.
+    Idle,
+}
+
+/// Event returned by [`PowerManager::poll`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerEvent {
+    /// The reader woke up, the discovery started.
+    TagApproached,
+    /// The discovery found a tag, it can be used with [`Nfc`].
+    TagFound,
+    /// The discovery found nothing, the reader backs off.
+    FalseWakeUp,
+}
+
+/// Time spent in each state and wake-up counters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PowerStats {
+    pub wakeups: u32,
+    pub false_wakeups: u32,
+    pub tags_found: u32,
+    pub sleeping_ms: u64,
+    pub discovering_ms: u64,
+    pub active_ms: u64,
+    pub idle_ms: u64,
+}
+
+impl PowerStats {
+    /// Share of time with the RF field on, in per mille.
+    pub fn duty_cycle_permille(&self) -> u32 {
+        let on = self.discovering_ms + self.active_ms;
+        let total = on + self.sleeping_ms + self.idle_ms;
+        if total == 0 {
+            return 0;
+        }
+        (on * 1000 / total) as u32
+    }
+}
+
+/// Cycles the reader between wake-up mode, discovery and idle, for battery
+/// powered devices. The discovery parameters must not enable the wake-up mode.
+pub struct PowerManager {
+    config: PowerConfig,
+    state: PowerState,
+    stats: PowerStats,
+    backoff_ms: u32,
+    // tick of the last state change and end of the current timeout
+    since: u32,
+    deadline: u32,
+}
+
+impl PowerManager {
+    pub fn new(config: PowerConfig) -> Self {
+        Self {
+            config,
+            state: PowerState::Stopped,
+            stats: PowerStats::default(),
+            backoff_ms: config.backoff_min_ms,
+            since: 0,
+            deadline: 0,
+        }
+    }
+    /// Signals a falling edge of IRQ_OUT, safe to call from the interrupt handler.
+    pub fn on_irq() {
+        TAG_APPROACHED.store(true, Ordering::Release);
+    }
+    /// Whether IRQ_OUT fell since the reader went to sleep.
+    pub fn tag_approached() -> bool {
+        TAG_APPROACHED.load(Ordering::Acquire)
+    }
+    pub fn state(&self) -> PowerState {
+        self.state
+    }
+    pub fn stats(&self) -> &PowerStats {
+        &self.stats
+    }
+    pub fn reset_stats(&mut self) {
+        self.stats = PowerStats::default();
+    }
+    /// Puts the reader to sleep until a tag approaches.
+    pub fn start(&mut self) -> Result<()> {
+        self.sleep(now())
+    }
+    /// Leaves the wake-up mode or stops the discovery.
+    pub fn stop(&mut self, nfc: &Nfc) -> Result<()> {
+        match self.state {
+            PowerState::Sleeping => WakeUp::stop()?,
+            PowerState::Discovering | PowerState::Active => nfc.deactivate_and_idle()?,
+            PowerState::Stopped | PowerState::Idle => {}
+        }
+        self.enter(PowerState::Stopped, now());
+        Ok(())
+    }
+    /// Deactivates the tag found and goes idle for the minimum backoff.
+    pub fn release(&mut self, nfc: &Nfc) -> Result<()> {
+        nfc.deactivate_and_idle()?;
+        self.backoff_ms = self.config.backoff_min_ms;
+        self.idle(now());
+        Ok(())
+    }
+    /// Runs the cycle, to call periodically and after [`PowerManager::on_irq`].
+    pub fn poll(&mut self, discover: &Discover, nfc: &Nfc) -> Result<Option<PowerEvent>> {
+        let now = now();
+        match self.state {
+            PowerState::Sleeping => {
+                if self.config.irq_driven && !Self::tag_approached() {
+                    return Ok(None);
+                }
+                nfc.worker();
+                if !WakeUp::has_woken() {
+                    return Ok(None);
+                }
+                WakeUp::stop()?;
+                discover.start()?;
+                self.stats.wakeups += 1;
+                self.enter(PowerState::Discovering, now);
+                self.deadline = deadline(now, self.config.discovery_timeout_ms);
+                Ok(Some(PowerEvent::TagApproached))
+            }
+            PowerState::Discovering => {
+                nfc.worker();
+                if matches!(
+                    nfc.state(),
+                    rfalNfcState::RFAL_NFC_STATE_ACTIVATED
+                        | rfalNfcState::RFAL_NFC_STATE_POLL_SELECT
+                ) {
+                    self.stats.tags_found += 1;
+                    self.backoff_ms = self.config.backoff_min_ms;
+                    self.enter(PowerState::Active, now);
+                    return Ok(Some(PowerEvent::TagFound));
+                }
+                if !expired(now, self.deadline) {
+                    return Ok(None);
+                }
+                nfc.deactivate_and_idle()?;
+                self.stats.false_wakeups += 1;
+                self.idle(now);
+                self.backoff_ms = self
+                    .backoff_ms
+                    .saturating_mul(2)
+                    .min(self.config.backoff_max_ms);
+                Ok(Some(PowerEvent::FalseWakeUp))
+            }
+            PowerState::Idle => {
+                if expired(now, self.deadline) {
+                    self.sleep(now)?;
+                }
+                Ok(None)
+            }
+            PowerState::Stopped | PowerState::Active => Ok(None),
+        }
+    }
+    fn sleep(&mut self, now: u32) -> Result<()> {
+        TAG_APPROACHED.store(false, Ordering::Release);
+        WakeUp::start(Some(&self.config.wakeup))?;
+        self.enter(PowerState::Sleeping, now);
+        Ok(())
+    }
+    fn idle(&mut self, now: u32) {
+        self.enter(PowerState::Idle, now);
+        self.deadline = deadline(now, self.backoff_ms);
+    }
+    fn enter(&mut self, state: PowerState, now: u32) {
+        let elapsed = now.wrapping_sub(self.since) as u64;
+        match self.state {
+            PowerState::Sleeping => self.stats.sleeping_ms += elapsed,
+            PowerState::Discovering => self.stats.discovering_ms += elapsed,
+            PowerState::Active => self.stats.active_ms += elapsed,
+            PowerState::Idle => self.stats.idle_ms += elapsed,
+            PowerState::Stopped => {}
+        }
+        self.state = state;
+        self.since = now;
+    }
+}
@@ -0,0 +1,126 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::ticks::{deadline, expired, now};
+use crate::{nfc::Device, result, rfal14443AShortFrameCmd, Error, Nfca, Poller, Result};
+use rfal_sys::rfalNfcRfInterface;
+
+/// How the presence of an activated device is checked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresenceMethod {
+    /// REQA or WUPA, the tag answers but leaves the active state and must be
+    /// selected again before the next exchange.
+    Nfca(rfal14443AShortFrameCmd),
+    /// Tag type specific read of the NDEF poller, which must be initialized.
+    Ndef,
+    /// R(NAK) answered by an R(ACK), between two ISO-DEP exchanges.
+    IsoDep,
+}
+
+impl PresenceMethod {
+    /// Method keeping `dev` activated: ISO-DEP if it is its interface, NDEF otherwise.
+    pub fn for_device(dev: &Device) -> Self {
+        match dev.0.rfInterface {
+            rfalNfcRfInterface::RFAL_NFC_INTERFACE_ISODEP => PresenceMethod::IsoDep,
+            _ => PresenceMethod::Ndef,
+        }
+    }
+    /// Checks once, usually failing with `Error::Timeout` when the device left.
+    pub fn check(&self, poller: &mut Poller) -> Result<()> {
+        match self {
+            PresenceMethod::Nfca(cmd) => Nfca::check_presence(*cmd).map(|_| ()),
+            PresenceMethod::Ndef => poller.check_presence(),
+            PresenceMethod::IsoDep => result(unsafe { rfal_sys::rfalIsoDepPollerCheckPresence() }),
+        }
+    }
+}
+
+/// Presence monitor configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PresenceConfig {
+    /// Time between two checks.
+    pub interval_ms: u32,
+    /// Consecutive failed checks after which the device counts as removed.
+    pub max_misses: u8,
+}
+
+impl Default for PresenceConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 100,
+            max_misses: 2,
+        }
+    }
+}
+
+/// Event returned by [`PresenceMonitor::poll`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresenceEvent {
+    /// A check failed, the device may be leaving the field.
+    Missed(Error),
+    /// The device left the field, reported once.
+    Removed,
+}
+
+/// Checks periodically that the activated device is still in the field.
+pub struct PresenceMonitor {
+    config: PresenceConfig,
+    method: PresenceMethod,
+    // tick of the next check
+    deadline: u32,
+    misses: u8,
+    removed: bool,
+}
+
+impl PresenceMonitor {
+    pub fn new(config: PresenceConfig, method: PresenceMethod) -> Self {
+        Self {
+            config,
+            method,
+            deadline: deadline(now(), config.interval_ms),
+            misses: 0,
+            removed: false,
+        }
+    }
+    /// Monitor of the activated `dev`, see [`PresenceMethod::for_device`].
+    pub fn for_device(config: PresenceConfig, dev: &Device) -> Self {
+        Self::new(config, PresenceMethod::for_device(dev))
+    }
+    pub fn method(&self) -> PresenceMethod {
+        self.method
+    }
+    pub fn is_removed(&self) -> bool {
+        self.removed
+    }
+    /// Restarts the monitoring, for a newly activated device.
+    pub fn reset(&mut self) {
+        self.deadline = deadline(now(), self.config.interval_ms);
+        self.misses = 0;
+        self.removed = false;
+    }
+    /// Checks the presence once the interval expired, to call periodically
+    /// between exchanges. `poller` is only used by [`PresenceMethod::Ndef`].
+    pub fn poll(&mut self, poller: &mut Poller) -> Result<Option<PresenceEvent>> {
+        let now = now();
+        if self.removed || !expired(now, self.deadline) {
+            return Ok(None);
+        }
+        self.deadline = deadline(now, self.config.interval_ms);
+        match self.method.check(poller) {
+            Ok(()) => {
+                self.misses = 0;
+                Ok(None)
+            }
+            // not a sign of removal, the monitor is misused
+            Err(err @ (Error::NotInitialized | Error::WrongState | Error::Param)) => Err(err),
+            Err(err) => {
+                self.misses = self.misses.saturating_add(1);
+                if self.misses < self.config.max_misses {
+                    return Ok(Some(PresenceEvent::Missed(err)));
+                }
+                self.removed = true;
+                Ok(Some(PresenceEvent::Removed))
+            }
+        }
+    }
+}
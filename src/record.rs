@@ -0,0 +1,415 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use core::marker::PhantomData;
+
+use crate::{
+    result, Aar, DeviceInfo, Error, External, Media, Result, ServiceParameter, ServiceSelect,
+    SmartPoster, Text, TnepStatus, Uri, VCard, WlcCapability, WlcListenControl, WlcPollInfo,
+    WlcStatusInfo,
+};
+use rfal_sys::{ndefBuffer, ndefConstBuffer, ndefConstBuffer8, ndefRecord, ndefType, ndefTypeId};
+
+const HEADER_MB: u8 = 0x80;
+const HEADER_ME: u8 = 0x40;
+
+/// Type Name Format of an NDEF record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tnf {
+    Empty,
+    WellKnown,
+    Media,
+    Uri,
+    External,
+    Unknown,
+    Unchanged,
+    Reserved,
+}
+
+impl From<u8> for Tnf {
+    fn from(value: u8) -> Self {
+        match value as u32 & rfal_sys::NDEF_TNF_MASK {
+            rfal_sys::NDEF_TNF_EMPTY => Tnf::Empty,
+            rfal_sys::NDEF_TNF_RTD_WELL_KNOWN_TYPE => Tnf::WellKnown,
+            rfal_sys::NDEF_TNF_MEDIA_TYPE => Tnf::Media,
+            rfal_sys::NDEF_TNF_URI => Tnf::Uri,
+            rfal_sys::NDEF_TNF_RTD_EXTERNAL_TYPE => Tnf::External,
+            rfal_sys::NDEF_TNF_UNKNOWN => Tnf::Unknown,
+            rfal_sys::NDEF_TNF_UNCHANGED => Tnf::Unchanged,
+            _ => Tnf::Reserved,
+        }
+    }
+}
+
+impl From<Tnf> for u8 {
+    fn from(value: Tnf) -> Self {
+        (match value {
+            Tnf::Empty => rfal_sys::NDEF_TNF_EMPTY,
+            Tnf::WellKnown => rfal_sys::NDEF_TNF_RTD_WELL_KNOWN_TYPE,
+            Tnf::Media => rfal_sys::NDEF_TNF_MEDIA_TYPE,
+            Tnf::Uri => rfal_sys::NDEF_TNF_URI,
+            Tnf::External => rfal_sys::NDEF_TNF_RTD_EXTERNAL_TYPE,
+            Tnf::Unknown => rfal_sys::NDEF_TNF_UNKNOWN,
+            Tnf::Unchanged => rfal_sys::NDEF_TNF_UNCHANGED,
+            Tnf::Reserved => rfal_sys::NDEF_TNF_RESERVED,
+        }) as u8
+    }
+}
+
+/// Anything that can be serialized as a single NDEF record.
+pub trait Encode {
+    /// Encodes the record into `buf` and returns the number of bytes used.
+    /// The MB/ME flags are left cleared, [`MessageWriter`] takes care of them.
+    fn encode(&self, buf: &mut [u8]) -> Result<usize>;
+}
+
+/// A raw NDEF record, borrowing its type, id and payload from a buffer.
+#[derive(Clone, Copy)]
+pub struct Record<'a> {
+    raw: ndefRecord,
+    _buf: PhantomData<&'a [u8]>,
+}
+
+impl<'a> Record<'a> {
+    pub fn new(
+        tnf: Tnf,
+        record_type: &'a [u8],
+        id: Option<&'a [u8]>,
+        payload: &'a [u8],
+    ) -> Result<Self> {
+        if record_type.len() > u8::MAX as usize || id.is_some_and(|id| id.len() > u8::MAX as usize)
+        {
+            return Err(Error::Param);
+        }
+        let mut raw = empty_record();
+        result(unsafe {
+            rfal_sys::ndefRecordInit(
+                &mut raw,
+                tnf.into(),
+                &const_buffer8(record_type),
+                &const_buffer8(id.unwrap_or_default()),
+                &const_buffer(payload),
+            )
+        })?;
+        Ok(Self {
+            raw,
+            _buf: PhantomData,
+        })
+    }
+    /// Decodes the first record found in `buf`.
+    pub fn decode(buf: &'a [u8]) -> Result<Self> {
+        let mut raw = empty_record();
+        result(unsafe { rfal_sys::ndefRecordDecode(&const_buffer(buf), &mut raw) })?;
+        Ok(Self {
+            raw,
+            _buf: PhantomData,
+        })
+    }
+    pub fn tnf(&self) -> Tnf {
+        Tnf::from(self.raw.header)
+    }
+    pub fn is_message_begin(&self) -> bool {
+        self.raw.header & HEADER_MB != 0
+    }
+    pub fn is_message_end(&self) -> bool {
+        self.raw.header & HEADER_ME != 0
+    }
+    pub fn record_type(&self) -> &'a [u8] {
+        unsafe { slice(self.raw.type_, self.raw.typeLength as usize) }
+    }
+    pub fn id(&self) -> Option<&'a [u8]> {
+        if self.raw.idLength != 0 {
+            Some(unsafe { slice(self.raw.id, self.raw.idLength as usize) })
+        } else {
+            None
+        }
+    }
+    pub fn payload(&self) -> &'a [u8] {
+        unsafe {
+            slice(
+                self.raw.bufPayload.buffer,
+                self.raw.bufPayload.length as usize,
+            )
+        }
+    }
+    /// Length of the whole record (header included) once encoded.
+    pub fn encoded_len(&self) -> usize {
+        unsafe { rfal_sys::ndefRecordGetLength(&self.raw) as usize }
+    }
+    pub fn is_type(&self, tnf: Tnf, record_type: &[u8]) -> bool {
+        record_type.len() <= u8::MAX as usize
+            && unsafe {
+                rfal_sys::ndefRecordTypeMatch(&self.raw, tnf.into(), &const_buffer8(record_type))
+            }
+    }
+    /// Converts the record to one of the supported typed records,
+    /// falling back to [`TypedRecord::Other`].
+    pub fn typed(&self) -> Result<TypedRecord<'a>> {
+        match self.tnf() {
+            Tnf::Empty => return Ok(TypedRecord::Empty),
+            Tnf::WellKnown if self.record_type() == SmartPoster::RECORD_TYPE => {
+                return SmartPoster::decode(self.payload()).map(TypedRecord::SmartPoster)
+            }
+            Tnf::Media if VCard::is_vcard(self.record_type()) => {
+                return VCard::decode(self.payload()).map(TypedRecord::VCard)
+            }
+            _ => {}
+        }
+        let ty = self.to_type()?;
+        Ok(match ty.id {
+            ndefTypeId::NDEF_TYPE_ID_RTD_DEVICE_INFO => {
+                TypedRecord::DeviceInfo(DeviceInfo::from_type(&ty)?)
+            }
+            ndefTypeId::NDEF_TYPE_ID_RTD_TEXT => TypedRecord::Text(Text::from_type(&ty)?),
+            ndefTypeId::NDEF_TYPE_ID_RTD_URI => TypedRecord::Uri(Uri::from_type(&ty)?),
+            ndefTypeId::NDEF_TYPE_ID_RTD_AAR => TypedRecord::Aar(Aar::from_type(&ty)?),
+            ndefTypeId::NDEF_TYPE_ID_RTD_WLCCAP => {
+                TypedRecord::WlcCapability(WlcCapability::from_type(&ty)?)
+            }
+            ndefTypeId::NDEF_TYPE_ID_RTD_WLCSTAI => {
+                TypedRecord::WlcStatusInfo(WlcStatusInfo::from_type(&ty)?)
+            }
+            ndefTypeId::NDEF_TYPE_ID_RTD_WLCINFO => {
+                TypedRecord::WlcPollInfo(WlcPollInfo::from_type(&ty)?)
+            }
+            ndefTypeId::NDEF_TYPE_ID_RTD_WLCCTL => {
+                TypedRecord::WlcListenControl(WlcListenControl::from_type(&ty)?)
+            }
+            ndefTypeId::NDEF_TYPE_ID_RTD_TNEP_SERVICE_PARAMETER => {
+                TypedRecord::TnepServiceParameter(ServiceParameter::from_type(&ty)?)
+            }
+            ndefTypeId::NDEF_TYPE_ID_RTD_TNEP_SERVICE_SELECT => {
+                TypedRecord::TnepServiceSelect(ServiceSelect::from_type(&ty)?)
+            }
+            ndefTypeId::NDEF_TYPE_ID_RTD_TNEP_STATUS => {
+                TypedRecord::TnepStatus(TnepStatus::from_type(&ty)?)
+            }
+            _ => match self.tnf() {
+                Tnf::Media => TypedRecord::Media(Media::new(self.record_type(), self.payload())?),
+                Tnf::External => {
+                    TypedRecord::External(External::new(self.record_type(), self.payload())?)
+                }
+                _ => TypedRecord::Other(*self),
+            },
+        })
+    }
+    pub(crate) fn to_type(self) -> Result<ndefType> {
+        let mut ty = empty_type();
+        result(unsafe { rfal_sys::ndefRecordToType(&self.raw, &mut ty) })?;
+        Ok(ty)
+    }
+}
+
+impl Encode for Record<'_> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        encode_record(&self.raw, buf)
+    }
+}
+
+impl core::fmt::Debug for Record<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Record")
+            .field("tnf", &self.tnf())
+            .field("record_type", &self.record_type())
+            .field("id", &self.id())
+            .field("payload", &self.payload())
+            .finish()
+    }
+}
+
+/// Typed view on the records found in the wild.
+#[derive(Debug)]
+pub enum TypedRecord<'a> {
+    Empty,
+    Text(Text<'a>),
+    Uri(Uri<'a>),
+    SmartPoster(SmartPoster<'a>),
+    DeviceInfo(DeviceInfo<'a>),
+    Aar(Aar<'a>),
+    External(External<'a>),
+    VCard(VCard<'a>),
+    Media(Media<'a>),
+    TnepServiceParameter(ServiceParameter<'a>),
+    TnepServiceSelect(ServiceSelect<'a>),
+    TnepStatus(TnepStatus),
+    WlcCapability(WlcCapability),
+    WlcStatusInfo(WlcStatusInfo),
+    WlcPollInfo(WlcPollInfo),
+    WlcListenControl(WlcListenControl),
+    Other(Record<'a>),
+}
+
+impl Encode for TypedRecord<'_> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        match self {
+            TypedRecord::Empty => Record::new(Tnf::Empty, &[], None, &[])?.encode(buf),
+            TypedRecord::Text(text) => text.encode(buf),
+            TypedRecord::Uri(uri) => uri.encode(buf),
+            TypedRecord::SmartPoster(sp) => sp.encode(buf),
+            TypedRecord::DeviceInfo(info) => info.encode(buf),
+            TypedRecord::Aar(aar) => aar.encode(buf),
+            TypedRecord::External(ext) => ext.encode(buf),
+            TypedRecord::VCard(vcard) => vcard.encode(buf),
+            TypedRecord::Media(media) => media.encode(buf),
+            TypedRecord::TnepServiceParameter(param) => param.encode(buf),
+            TypedRecord::TnepServiceSelect(select) => select.encode(buf),
+            TypedRecord::TnepStatus(status) => status.encode(buf),
+            TypedRecord::WlcCapability(cap) => cap.encode(buf),
+            TypedRecord::WlcStatusInfo(info) => info.encode(buf),
+            TypedRecord::WlcPollInfo(info) => info.encode(buf),
+            TypedRecord::WlcListenControl(ctl) => ctl.encode(buf),
+            TypedRecord::Other(record) => record.encode(buf),
+        }
+    }
+}
+
+/// Iterator over the records of a raw NDEF message.
+pub struct Records<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Records<'a> {
+    pub fn new(msg: &'a [u8]) -> Self {
+        Self { buf: msg }
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Result<Record<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        match Record::decode(self.buf) {
+            Ok(record) => {
+                let len = record.encoded_len().min(self.buf.len());
+                self.buf = &self.buf[len..];
+                Some(Ok(record))
+            }
+            Err(e) => {
+                self.buf = &[];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Builds a raw NDEF message into a caller provided buffer,
+/// setting the MB/ME flags of the first/last records.
+pub struct MessageWriter<'b> {
+    buf: &'b mut [u8],
+    len: usize,
+    last: Option<usize>,
+}
+
+impl<'b> MessageWriter<'b> {
+    pub fn new(buf: &'b mut [u8]) -> Self {
+        Self {
+            buf,
+            len: 0,
+            last: None,
+        }
+    }
+    pub fn push(&mut self, record: &impl Encode) -> Result<()> {
+        let start = self.len;
+        let len = record.encode(&mut self.buf[start..])?;
+        self.buf[start] &= !(HEADER_MB | HEADER_ME);
+        if self.last.is_none() {
+            self.buf[start] |= HEADER_MB;
+        }
+        self.last = Some(start);
+        self.len += len;
+        Ok(())
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.last.is_none()
+    }
+    pub fn finish(self) -> &'b [u8] {
+        let buf = self.buf;
+        if let Some(last) = self.last {
+            buf[last] |= HEADER_ME;
+        }
+        &buf[..self.len]
+    }
+}
+
+pub(crate) fn empty_record() -> ndefRecord {
+    // allocate default values manually, thanks bingen to not deriving Default trait...
+    ndefRecord {
+        header: 0,
+        typeLength: 0,
+        idLength: 0,
+        type_: core::ptr::null(),
+        id: core::ptr::null(),
+        bufPayload: ndefConstBuffer {
+            buffer: core::ptr::null(),
+            length: 0,
+        },
+        ndeftype: core::ptr::null(),
+        next: core::ptr::null_mut(),
+    }
+}
+
+pub(crate) fn empty_type() -> ndefType {
+    // allocate default values manually, thanks bingen to not deriving Default trait...
+    ndefType {
+        id: ndefTypeId::NDEF_TYPE_ID_NONE,
+        getPayloadLength: None,
+        getPayloadItem: None,
+        typeToRecord: None,
+        data: rfal_sys::ndefTypeStruct__bindgen_ty_1 { reserved: 0 },
+    }
+}
+
+/// Encodes a typed record, `ty` must outlive the call as the C record points to it.
+pub(crate) fn encode_type(ty: &ndefType, buf: &mut [u8]) -> Result<usize> {
+    let mut record = empty_record();
+    result(unsafe { rfal_sys::ndefTypeToRecord(ty, &mut record) })?;
+    encode_record(&record, buf)
+}
+
+fn encode_record(record: &ndefRecord, buf: &mut [u8]) -> Result<usize> {
+    let mut out = ndefBuffer {
+        buffer: buf.as_mut_ptr(),
+        length: buf.len() as u32,
+    };
+    result(unsafe { rfal_sys::ndefRecordEncode(record, &mut out) })?;
+    Ok(out.length as usize)
+}
+
+pub(crate) fn const_buffer(buf: &[u8]) -> ndefConstBuffer {
+    ndefConstBuffer {
+        buffer: if buf.is_empty() {
+            core::ptr::null()
+        } else {
+            buf.as_ptr()
+        },
+        length: buf.len() as u32,
+    }
+}
+
+pub(crate) fn const_buffer8(buf: &[u8]) -> ndefConstBuffer8 {
+    ndefConstBuffer8 {
+        buffer: if buf.is_empty() {
+            core::ptr::null()
+        } else {
+            buf.as_ptr()
+        },
+        length: buf.len() as u8,
+    }
+}
+
+/// # Safety
+///
+/// `ptr` must be null or valid for `len` bytes during `'a`.
+pub(crate) unsafe fn slice<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    }
+}
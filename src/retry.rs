@@ -0,0 +1,186 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::ticks::{deadline, expired, now};
+use crate::{rfalNfcState, Error, ErrorKind, Nfc, Result, NFCA_UID_MAX_LEN};
+
+/// How the device is brought back before trying again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reactivation {
+    /// Tries again on the device as it is.
+    None,
+    /// Restarts the discovery, selecting the first device found if several are.
+    Discovery,
+    /// Puts the device to sleep and selects the device at this index again.
+    Select(u8),
+}
+
+/// When and how a failed operation is tried again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of tries, including the first one, 1 disables the retries.
+    pub attempts: u8,
+    /// Delay before the second try, doubled before each next one.
+    pub backoff_ms: u32,
+    pub backoff_max_ms: u32,
+    /// Kinds of errors worth trying again.
+    pub retryable: &'static [ErrorKind],
+    pub reactivation: Reactivation,
+    /// Time given to the reactivation before giving up with `Error::Timeout`.
+    pub reactivation_timeout_ms: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff_ms: 10,
+            backoff_max_ms: 100,
+            retryable: &[ErrorKind::Transient],
+            reactivation: Reactivation::None,
+            reactivation_timeout_ms: 500,
+        }
+    }
+}
+
+/// How often the retries saved an operation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RetryStats {
+    pub operations: u32,
+    /// Tries after the first one.
+    pub retries: u32,
+    /// Operations succeeding after at least one retry.
+    pub recovered: u32,
+    /// Operations failing after at least one retry.
+    pub unrecovered: u32,
+    pub reactivations: u32,
+}
+
+/// Applies a [`RetryPolicy`], either set on [`Nfc`] and [`crate::Poller`] for
+/// their exchanges with the device or given to [`Nfc::with_retry`] and
+/// [`crate::Poller::with_retry`] for a single operation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Retry {
+    pub policy: RetryPolicy,
+    stats: RetryStats,
+}
+
+impl Retry {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy,
+            stats: RetryStats::default(),
+        }
+    }
+    pub fn stats(&self) -> &RetryStats {
+        &self.stats
+    }
+    pub fn reset_stats(&mut self) {
+        self.stats = RetryStats::default();
+    }
+    /// Runs `op` on `ctx` until it succeeds, fails with an error not retryable
+    /// or runs out of tries, `reactivated` restores `ctx` after a reactivation.
+    pub(crate) fn run<C, T>(
+        &mut self,
+        ctx: &mut C,
+        mut op: impl FnMut(&mut C) -> Result<T>,
+        mut reactivated: impl FnMut(&mut C) -> Result<()>,
+    ) -> Result<T> {
+        self.stats.operations += 1;
+        let mut backoff_ms = self.policy.backoff_ms;
+        let mut attempt = 1;
+        loop {
+            let err = match op(ctx) {
+                Ok(value) => {
+                    if attempt > 1 {
+                        self.stats.recovered += 1;
+                    }
+                    return Ok(value);
+                }
+                Err(err) => err,
+            };
+            if attempt >= self.policy.attempts || !self.policy.retryable.contains(&err.kind()) {
+                if attempt > 1 {
+                    self.stats.unrecovered += 1;
+                }
+                return Err(err);
+            }
+            attempt += 1;
+            self.stats.retries += 1;
+            rfal_sys::ffi_delay_ms(backoff_ms);
+            backoff_ms = backoff_ms.saturating_mul(2).min(self.policy.backoff_max_ms);
+            if self.policy.reactivation != Reactivation::None {
+                self.stats.reactivations += 1;
+                if let Err(err) = self.reactivate().and_then(|_| reactivated(ctx)) {
+                    self.stats.unrecovered += 1;
+                    return Err(err);
+                }
+            }
+        }
+    }
+    // activates the same device again, fails with `Error::NotFound` if another one answers
+    fn reactivate(&self) -> Result<()> {
+        // Nfc only holds the state of its data exchange, RFAL keeps the rest
+        let nfc = Nfc::default();
+        let mut id = [0u8; NFCA_UID_MAX_LEN];
+        let id_len = match nfc.active_device()?.id() {
+            Some(dev_id) if dev_id.len() <= id.len() => {
+                id[..dev_id.len()].copy_from_slice(dev_id);
+                dev_id.len()
+            }
+            _ => 0,
+        };
+        let deadline = deadline(now(), self.policy.reactivation_timeout_ms);
+        match self.policy.reactivation {
+            Reactivation::None => return Ok(()),
+            Reactivation::Discovery => {
+                nfc.deactivate_and_discovery()?;
+                if wait_state(&nfc, deadline)? == rfalNfcState::RFAL_NFC_STATE_POLL_SELECT {
+                    nfc.select(0)?;
+                    wait_state(&nfc, deadline)?;
+                }
+            }
+            Reactivation::Select(dev_idx) => {
+                nfc.deactivate_and_sleep()?;
+                wait_state(&nfc, deadline)?;
+                nfc.select(dev_idx)?;
+                wait_state(&nfc, deadline)?;
+            }
+        }
+        if nfc.state() != rfalNfcState::RFAL_NFC_STATE_ACTIVATED {
+            return Err(Error::WrongState);
+        }
+        if id_len != 0 && nfc.active_device()?.id() != Some(&id[..id_len]) {
+            return Err(Error::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl Nfc {
+    /// Runs `op` with the retries of `retry`.
+    pub fn with_retry<T>(
+        &self,
+        retry: &mut Retry,
+        mut op: impl FnMut(&Nfc) -> Result<T>,
+    ) -> Result<T> {
+        retry.run(&mut (), |_| op(self), |_| Ok(()))
+    }
+}
+
+// runs the worker until the device is activated or waits for a selection
+fn wait_state(nfc: &Nfc, deadline: u32) -> Result<rfalNfcState> {
+    loop {
+        nfc.worker();
+        let state = nfc.state();
+        if matches!(
+            state,
+            rfalNfcState::RFAL_NFC_STATE_ACTIVATED | rfalNfcState::RFAL_NFC_STATE_POLL_SELECT
+        ) {
+            return Ok(state);
+        }
+        if expired(now(), deadline) {
+            return Err(Error::Timeout);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{result, Error, Result};
+use rfal_sys::{rfalBitRate, rfalEHandling, rfalMode};
+
+// RFAL_1MS_IN_1FC and RFAL_US_IN_MS, casted macros are not exported by bindgen
+const FC_PER_MS: u64 = 13560;
+const US_PER_MS: u64 = 1000;
+
+/// Converts `us` microseconds to 1/fc cycles, as `rfalConvUsTo1fc` without overflow.
+/// Saturates below `RFAL_FWT_NONE` so that a long time never means no timeout.
+pub fn us_to_1fc(us: u32) -> u32 {
+    (us as u64 * FC_PER_MS / US_PER_MS).min(rfal_sys::RFAL_FWT_NONE as u64 - 1) as u32
+}
+
+/// Converts `fc` 1/fc cycles to microseconds, as `rfalConv1fcToUs` without overflow.
+pub fn fc_to_us(fc: u32) -> u32 {
+    (fc as u64 * US_PER_MS / FC_PER_MS) as u32
+}
+
+/// RF mode of the reader.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    None,
+    PollNfca,
+    PollNfcaT1t,
+    PollNfcb,
+    PollBPrime,
+    PollBCts,
+    PollNfcf,
+    PollNfcv,
+    PollPicopass,
+    PollActiveP2p,
+    ListenNfca,
+    ListenNfcb,
+    ListenNfcf,
+    ListenActiveP2p,
+}
+
+impl From<Mode> for rfalMode {
+    fn from(value: Mode) -> Self {
+        match value {
+            Mode::None => rfalMode::RFAL_MODE_NONE,
+            Mode::PollNfca => rfalMode::RFAL_MODE_POLL_NFCA,
+            Mode::PollNfcaT1t => rfalMode::RFAL_MODE_POLL_NFCA_T1T,
+            Mode::PollNfcb => rfalMode::RFAL_MODE_POLL_NFCB,
+            Mode::PollBPrime => rfalMode::RFAL_MODE_POLL_B_PRIME,
+            Mode::PollBCts => rfalMode::RFAL_MODE_POLL_B_CTS,
+            Mode::PollNfcf => rfalMode::RFAL_MODE_POLL_NFCF,
+            Mode::PollNfcv => rfalMode::RFAL_MODE_POLL_NFCV,
+            Mode::PollPicopass => rfalMode::RFAL_MODE_POLL_PICOPASS,
+            Mode::PollActiveP2p => rfalMode::RFAL_MODE_POLL_ACTIVE_P2P,
+            Mode::ListenNfca => rfalMode::RFAL_MODE_LISTEN_NFCA,
+            Mode::ListenNfcb => rfalMode::RFAL_MODE_LISTEN_NFCB,
+            Mode::ListenNfcf => rfalMode::RFAL_MODE_LISTEN_NFCF,
+            Mode::ListenActiveP2p => rfalMode::RFAL_MODE_LISTEN_ACTIVE_P2P,
+        }
+    }
+}
+
+impl From<rfalMode> for Mode {
+    fn from(value: rfalMode) -> Self {
+        match value {
+            rfalMode::RFAL_MODE_NONE => Mode::None,
+            rfalMode::RFAL_MODE_POLL_NFCA => Mode::PollNfca,
+            rfalMode::RFAL_MODE_POLL_NFCA_T1T => Mode::PollNfcaT1t,
+            rfalMode::RFAL_MODE_POLL_NFCB => Mode::PollNfcb,
+            rfalMode::RFAL_MODE_POLL_B_PRIME => Mode::PollBPrime,
+            rfalMode::RFAL_MODE_POLL_B_CTS => Mode::PollBCts,
+            rfalMode::RFAL_MODE_POLL_NFCF => Mode::PollNfcf,
+            rfalMode::RFAL_MODE_POLL_NFCV => Mode::PollNfcv,
+            rfalMode::RFAL_MODE_POLL_PICOPASS => Mode::PollPicopass,
+            rfalMode::RFAL_MODE_POLL_ACTIVE_P2P => Mode::PollActiveP2p,
+            rfalMode::RFAL_MODE_LISTEN_NFCA => Mode::ListenNfca,
+            rfalMode::RFAL_MODE_LISTEN_NFCB => Mode::ListenNfcb,
+            rfalMode::RFAL_MODE_LISTEN_NFCF => Mode::ListenNfcf,
+            rfalMode::RFAL_MODE_LISTEN_ACTIVE_P2P => Mode::ListenActiveP2p,
+        }
+    }
+}
+
+/// RF bit rate, in kbit/s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitRate {
+    Br106,
+    Br212,
+    Br424,
+    Br848,
+    Br1695,
+    Br3390,
+    Br6780,
+    Br13560,
+    /// NFC-V fast mode, VICC to VCD.
+    Br211p88,
+    /// NFC-V fast mode, VICC to VCD.
+    Br105p94,
+    /// NFC-V fast mode, VICC to VCD.
+    Br52p97,
+    /// NFC-V 1 out of 4.
+    Br26p48,
+    /// NFC-V 1 out of 256, VCD to VICC.
+    Br1p66,
+    /// Keeps the current bit rate, only for [`Rf::set_bit_rate`].
+    Keep,
+}
+
+impl From<BitRate> for rfalBitRate {
+    fn from(value: BitRate) -> Self {
+        match value {
+            BitRate::Br106 => rfalBitRate::RFAL_BR_106,
+            BitRate::Br212 => rfalBitRate::RFAL_BR_212,
+            BitRate::Br424 => rfalBitRate::RFAL_BR_424,
+            BitRate::Br848 => rfalBitRate::RFAL_BR_848,
+            BitRate::Br1695 => rfalBitRate::RFAL_BR_1695,
+            BitRate::Br3390 => rfalBitRate::RFAL_BR_3390,
+            BitRate::Br6780 => rfalBitRate::RFAL_BR_6780,
+            BitRate::Br13560 => rfalBitRate::RFAL_BR_13560,
+            BitRate::Br211p88 => rfalBitRate::RFAL_BR_211p88,
+            BitRate::Br105p94 => rfalBitRate::RFAL_BR_105p94,
+            BitRate::Br52p97 => rfalBitRate::RFAL_BR_52p97,
+            BitRate::Br26p48 => rfalBitRate::RFAL_BR_26p48,
+            BitRate::Br1p66 => rfalBitRate::RFAL_BR_1p66,
+            BitRate::Keep => rfalBitRate::RFAL_BR_KEEP,
+        }
+    }
+}
+
+impl From<rfalBitRate> for BitRate {
+    fn from(value: rfalBitRate) -> Self {
+        match value {
+            rfalBitRate::RFAL_BR_106 => BitRate::Br106,
+            rfalBitRate::RFAL_BR_212 => BitRate::Br212,
+            rfalBitRate::RFAL_BR_424 => BitRate::Br424,
+            rfalBitRate::RFAL_BR_848 => BitRate::Br848,
+            rfalBitRate::RFAL_BR_1695 => BitRate::Br1695,
+            rfalBitRate::RFAL_BR_3390 => BitRate::Br3390,
+            rfalBitRate::RFAL_BR_6780 => BitRate::Br6780,
+            rfalBitRate::RFAL_BR_13560 => BitRate::Br13560,
+            rfalBitRate::RFAL_BR_211p88 => BitRate::Br211p88,
+            rfalBitRate::RFAL_BR_105p94 => BitRate::Br105p94,
+            rfalBitRate::RFAL_BR_52p97 => BitRate::Br52p97,
+            rfalBitRate::RFAL_BR_26p48 => BitRate::Br26p48,
+            rfalBitRate::RFAL_BR_1p66 => BitRate::Br1p66,
+            rfalBitRate::RFAL_BR_KEEP => BitRate::Keep,
+        }
+    }
+}
+
+/// Error handling applied on reception.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorHandling {
+    None,
+    /// EMD suppression, Digital 2.1 4.1.1.1.
+    Emd,
+}
+
+impl From<ErrorHandling> for rfalEHandling {
+    fn from(value: ErrorHandling) -> Self {
+        match value {
+            ErrorHandling::None => rfalEHandling::RFAL_ERRORHANDLING_NONE,
+            ErrorHandling::Emd => rfalEHandling::RFAL_ERRORHANDLING_EMD,
+        }
+    }
+}
+
+impl From<rfalEHandling> for ErrorHandling {
+    fn from(value: rfalEHandling) -> Self {
+        match value {
+            rfalEHandling::RFAL_ERRORHANDLING_NONE => ErrorHandling::None,
+            rfalEHandling::RFAL_ERRORHANDLING_EMD => ErrorHandling::Emd,
+        }
+    }
+}
+
+/// Framing of a transceive, [`Default`] lets the chip handle CRC and parity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxRxFlags {
+    /// Append the CRC on transmission, otherwise it is part of the buffer.
+    pub crc_tx: bool,
+    /// Check the received CRC.
+    pub crc_rx_check: bool,
+    /// Remove the received CRC from the buffer.
+    pub crc_rx_remove: bool,
+    /// Generate the ISO 14443-A parity bits, otherwise they are part of the buffer.
+    pub parity_tx: bool,
+    /// Check and remove the received parity bits.
+    pub parity_rx: bool,
+    /// NFCIP-1 framing, adds SB and LEN on transmission and skips SB on reception.
+    pub nfcip1: bool,
+    /// Automatic gain control, turn it off to better detect collisions.
+    pub agc: bool,
+    /// Adapt the ISO 15693 request flags to the communication parameters.
+    pub nfcv_flag_auto: bool,
+}
+
+impl Default for TxRxFlags {
+    fn default() -> Self {
+        Self {
+            crc_tx: true,
+            crc_rx_check: true,
+            crc_rx_remove: true,
+            parity_tx: true,
+            parity_rx: true,
+            nfcip1: false,
+            agc: true,
+            nfcv_flag_auto: true,
+        }
+    }
+}
+
+impl TxRxFlags {
+    /// Raw flags, 0 being `RFAL_TXRX_FLAGS_DEFAULT`.
+    pub fn bits(&self) -> u32 {
+        let mut flags = 0;
+        let mut set = |cond: bool, flag: u32| {
+            if cond {
+                flags |= flag;
+            }
+        };
+        set(!self.crc_tx, rfal_sys::RFAL_TXRX_FLAGS_CRC_TX_MANUAL);
+        set(!self.crc_rx_check, rfal_sys::RFAL_TXRX_FLAGS_CRC_RX_MANUAL);
+        set(!self.crc_rx_remove, rfal_sys::RFAL_TXRX_FLAGS_CRC_RX_KEEP);
+        set(!self.parity_tx, rfal_sys::RFAL_TXRX_FLAGS_PAR_TX_NONE);
+        set(!self.parity_rx, rfal_sys::RFAL_TXRX_FLAGS_PAR_RX_KEEP);
+        set(self.nfcip1, rfal_sys::RFAL_TXRX_FLAGS_NFCIP1_ON);
+        set(!self.agc, rfal_sys::RFAL_TXRX_FLAGS_AGC_OFF);
+        set(
+            !self.nfcv_flag_auto,
+            rfal_sys::RFAL_TXRX_FLAGS_NFCV_FLAG_MANUAL,
+        );
+        flags
+    }
+}
+
+/// Raw RF layer, bypassing the NFC discovery state machine.
+pub struct Rf;
+
+impl Rf {
+    /// Sets the RF mode and bit rates, the field is left as it is.
+    pub fn set_mode(mode: Mode, tx: BitRate, rx: BitRate) -> Result<()> {
+        result(unsafe { rfal_sys::rfalSetMode(mode.into(), tx.into(), rx.into()) })
+    }
+    pub fn mode() -> Mode {
+        unsafe { rfal_sys::rfalGetMode() }.into()
+    }
+    pub fn set_bit_rate(tx: BitRate, rx: BitRate) -> Result<()> {
+        result(unsafe { rfal_sys::rfalSetBitRate(tx.into(), rx.into()) })
+    }
+    /// Current transmission and reception bit rates.
+    pub fn bit_rate() -> Result<(BitRate, BitRate)> {
+        let mut tx = rfalBitRate::RFAL_BR_KEEP;
+        let mut rx = rfalBitRate::RFAL_BR_KEEP;
+        result(unsafe { rfal_sys::rfalGetBitRate(&mut tx, &mut rx) })?;
+        Ok((tx.into(), rx.into()))
+    }
+    pub fn set_error_handling(handling: ErrorHandling) {
+        unsafe { rfal_sys::rfalSetErrorHandling(handling.into()) }
+    }
+    pub fn error_handling() -> ErrorHandling {
+        unsafe { rfal_sys::rfalGetErrorHandling() }.into()
+    }
+    /// Turns the field on and starts the guard time set by [`Rf::set_gt_us`].
+    pub fn field_on_and_start_gt() -> Result<()> {
+        result(unsafe { rfal_sys::rfalFieldOnAndStartGT() })
+    }
+    pub fn field_off() -> Result<()> {
+        result(unsafe { rfal_sys::rfalFieldOff() })
+    }
+    /// Minimum time between the end of a reception and the next transmission.
+    pub fn set_fdt_poll_us(us: u32) {
+        unsafe { rfal_sys::rfalSetFDTPoll(us_to_1fc(us)) }
+    }
+    pub fn fdt_poll_us() -> u32 {
+        fc_to_us(unsafe { rfal_sys::rfalGetFDTPoll() })
+    }
+    /// Time after a transmission during which a response is ignored.
+    pub fn set_fdt_listen_us(us: u32) {
+        unsafe { rfal_sys::rfalSetFDTListen(us_to_1fc(us)) }
+    }
+    pub fn fdt_listen_us() -> u32 {
+        fc_to_us(unsafe { rfal_sys::rfalGetFDTListen() })
+    }
+    /// Guard time after the field is turned on, 0 to disable it.
+    pub fn set_gt_us(us: u32) {
+        unsafe { rfal_sys::rfalSetGT(us_to_1fc(us)) }
+    }
+    pub fn gt_us() -> u32 {
+        fc_to_us(unsafe { rfal_sys::rfalGetGT() })
+    }
+    pub fn is_gt_expired() -> bool {
+        unsafe { rfal_sys::rfalIsGTExpired() }
+    }
+    /// Sends `tx` and waits up to `fwt_us` microseconds, or forever with `None`,
+    /// for a response in `rx`. Returns the number of bytes received, an
+    /// incomplete last byte fails with `Error::IncompleteByte` and its bits.
+    pub fn transceive(
+        tx: &mut [u8],
+        rx: &mut [u8],
+        flags: TxRxFlags,
+        fwt_us: Option<u32>,
+    ) -> Result<usize> {
+        // length in bits, rfalTransceiveBlockingTxRx rounds it up to bytes
+        let mut rcv_bits = 0u16;
+        result(unsafe {
+            rfal_sys::rfalTransceiveBlockingTx(
+                tx.as_mut_ptr(),
+                tx.len() as u16,
+                rx.as_mut_ptr(),
+                rx.len() as u16,
+                &mut rcv_bits,
+                flags.bits(),
+                fwt_us.map_or(rfal_sys::RFAL_FWT_NONE, us_to_1fc),
+            )
+        })?;
+        match result(unsafe { rfal_sys::rfalTransceiveBlockingRx() }) {
+            Ok(()) => Ok((rcv_bits as usize).div_ceil(8)),
+            // the ST25R95 driver reports the residual bits in the length only
+            Err(Error::IncompleteByte(_)) => Err(Error::IncompleteByte((rcv_bits % 8) as u8)),
+            Err(err) => Err(err),
+        }
+    }
+}
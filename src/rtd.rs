@@ -0,0 +1,397 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{
+    record::{const_buffer, const_buffer8, empty_type, encode_type, slice},
+    result, Encode, Error, MessageWriter, Record, Records, Result, Tnf,
+};
+use rfal_sys::{ndefConstBuffer, ndefConstBuffer8, ndefDeviceInfoEntry, ndefType};
+
+/// RTD Text record.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Text<'a> {
+    pub utf16: bool,
+    pub language: &'a [u8],
+    pub text: &'a [u8],
+}
+
+impl<'a> Text<'a> {
+    pub(crate) const RECORD_TYPE: &'static [u8] = b"T";
+
+    pub fn decode(payload: &'a [u8]) -> Result<Self> {
+        let record = Record::new(Tnf::WellKnown, Self::RECORD_TYPE, None, payload)?;
+        Self::from_type(&record.to_type()?)
+    }
+
+    pub(crate) fn from_type(ty: &ndefType) -> Result<Self> {
+        let mut encoding = 0u8;
+        let mut language = ndefConstBuffer8 {
+            buffer: core::ptr::null(),
+            length: 0,
+        };
+        let mut text = ndefConstBuffer {
+            buffer: core::ptr::null(),
+            length: 0,
+        };
+        result(unsafe { rfal_sys::ndefGetRtdText(ty, &mut encoding, &mut language, &mut text) })?;
+        Ok(Self {
+            utf16: encoding as u32 == rfal_sys::TEXT_ENCODING_UTF16,
+            language: unsafe { slice(language.buffer, language.length as usize) },
+            text: unsafe { slice(text.buffer, text.length as usize) },
+        })
+    }
+    fn to_type(self) -> Result<ndefType> {
+        if self.language.len() > u8::MAX as usize {
+            return Err(Error::Param);
+        }
+        let encoding = if self.utf16 {
+            rfal_sys::TEXT_ENCODING_UTF16
+        } else {
+            rfal_sys::TEXT_ENCODING_UTF8
+        };
+        let mut ty = empty_type();
+        result(unsafe {
+            rfal_sys::ndefRtdTextInit(
+                &mut ty,
+                encoding as u8,
+                &const_buffer8(self.language),
+                &const_buffer(self.text),
+            )
+        })?;
+        Ok(ty)
+    }
+}
+
+impl Encode for Text<'_> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        encode_type(&self.to_type()?, buf)
+    }
+}
+
+/// RTD URI record, `prefix` being the URI identifier code (e.g. 0x04 for "https://").
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Uri<'a> {
+    pub prefix: u8,
+    pub uri: &'a [u8],
+}
+
+impl<'a> Uri<'a> {
+    pub(crate) const RECORD_TYPE: &'static [u8] = b"U";
+
+    pub fn decode(payload: &'a [u8]) -> Result<Self> {
+        let record = Record::new(Tnf::WellKnown, Self::RECORD_TYPE, None, payload)?;
+        Self::from_type(&record.to_type()?)
+    }
+    /// Expanded form of the identifier code, e.g. "https://".
+    pub fn prefix_str(&self) -> Result<&'static [u8]> {
+        let ty = self.to_type()?;
+        let mut protocol = ndefConstBuffer {
+            buffer: core::ptr::null(),
+            length: 0,
+        };
+        let mut uri = ndefConstBuffer {
+            buffer: core::ptr::null(),
+            length: 0,
+        };
+        result(unsafe { rfal_sys::ndefGetRtdUri(&ty, &mut protocol, &mut uri) })?;
+        Ok(unsafe { slice(protocol.buffer, protocol.length as usize) })
+    }
+    pub(crate) fn from_type(ty: &ndefType) -> Result<Self> {
+        if ty.id != rfal_sys::ndefTypeId::NDEF_TYPE_ID_RTD_URI {
+            return Err(Error::Param);
+        }
+        let uri = unsafe { ty.data.uri };
+        Ok(Self {
+            prefix: uri.protocol,
+            uri: unsafe { slice(uri.bufUriString.buffer, uri.bufUriString.length as usize) },
+        })
+    }
+    fn to_type(self) -> Result<ndefType> {
+        let mut ty = empty_type();
+        result(unsafe { rfal_sys::ndefRtdUriInit(&mut ty, self.prefix, &const_buffer(self.uri)) })?;
+        Ok(ty)
+    }
+}
+
+impl Encode for Uri<'_> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        encode_type(&self.to_type()?, buf)
+    }
+}
+
+/// Smart Poster recommended action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Do,
+    Save,
+    Open,
+    Rfu(u8),
+}
+
+impl From<u8> for Action {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Action::Do,
+            1 => Action::Save,
+            2 => Action::Open,
+            v => Action::Rfu(v),
+        }
+    }
+}
+
+impl From<Action> for u8 {
+    fn from(value: Action) -> Self {
+        match value {
+            Action::Do => 0,
+            Action::Save => 1,
+            Action::Open => 2,
+            Action::Rfu(v) => v,
+        }
+    }
+}
+
+/// RTD Smart Poster record: a nested message made of an URI,
+/// optional titles and an optional action.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SmartPoster<'a> {
+    pub uri: Uri<'a>,
+    pub title: Option<Text<'a>>,
+    pub action: Option<Action>,
+}
+
+impl<'a> SmartPoster<'a> {
+    pub(crate) const RECORD_TYPE: &'static [u8] = b"Sp";
+    const ACTION_RECORD_TYPE: &'static [u8] = b"act";
+    const HEADER_MAX_LEN: usize = 8;
+
+    pub fn decode(payload: &'a [u8]) -> Result<Self> {
+        let mut uri = None;
+        let mut title = None;
+        let mut action = None;
+        for record in Records::new(payload) {
+            let record = record?;
+            if record.tnf() != Tnf::WellKnown {
+                continue;
+            }
+            match record.record_type() {
+                Uri::RECORD_TYPE => uri = Some(Uri::from_type(&record.to_type()?)?),
+                Text::RECORD_TYPE if title.is_none() => {
+                    title = Some(Text::from_type(&record.to_type()?)?)
+                }
+                Self::ACTION_RECORD_TYPE => action = record.payload().first().map(|&a| a.into()),
+                _ => {}
+            }
+        }
+        Ok(Self {
+            uri: uri.ok_or(Error::Proto)?,
+            title,
+            action,
+        })
+    }
+}
+
+impl Encode for SmartPoster<'_> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        if buf.len() < Self::HEADER_MAX_LEN {
+            return Err(Error::NoMem);
+        }
+        // build the nested message after the largest possible header,
+        // then move it back if a short record header fits
+        let (header, nested) = buf.split_at_mut(Self::HEADER_MAX_LEN);
+        let mut msg = MessageWriter::new(nested);
+        msg.push(&self.uri)?;
+        if let Some(title) = &self.title {
+            msg.push(title)?;
+        }
+        let action = self.action.map(|a| [u8::from(a)]);
+        if let Some(action) = &action {
+            msg.push(&Record::new(
+                Tnf::WellKnown,
+                Self::ACTION_RECORD_TYPE,
+                None,
+                action,
+            )?)?;
+        }
+        let payload_len = msg.finish().len();
+
+        let type_len = Self::RECORD_TYPE.len();
+        let header_len = if payload_len <= u8::MAX as usize {
+            header[0] = 0x10 | u8::from(Tnf::WellKnown); // SR
+            header[1] = type_len as u8;
+            header[2] = payload_len as u8;
+            3
+        } else {
+            header[0] = u8::from(Tnf::WellKnown);
+            header[1] = type_len as u8;
+            header[2..6].copy_from_slice(&(payload_len as u32).to_be_bytes());
+            6
+        };
+        buf[header_len..header_len + type_len].copy_from_slice(Self::RECORD_TYPE);
+        let start = header_len + type_len;
+        buf.copy_within(
+            Self::HEADER_MAX_LEN..Self::HEADER_MAX_LEN + payload_len,
+            start,
+        );
+        Ok(start + payload_len)
+    }
+}
+
+/// RTD Device Information record.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeviceInfo<'a> {
+    pub manufacturer: &'a [u8],
+    pub model: &'a [u8],
+    pub unique_name: Option<&'a [u8]>,
+    pub uuid: Option<&'a [u8]>,
+    pub firmware_version: Option<&'a [u8]>,
+}
+
+impl<'a> DeviceInfo<'a> {
+    pub fn decode(payload: &'a [u8]) -> Result<Self> {
+        let record = Record::new(Tnf::WellKnown, b"Di", None, payload)?;
+        Self::from_type(&record.to_type()?)
+    }
+    pub(crate) fn from_type(ty: &ndefType) -> Result<Self> {
+        let mut data = rfal_sys::ndefTypeRtdDeviceInfo {
+            devInfo: [ndefDeviceInfoEntry {
+                type_: 0,
+                length: 0,
+                buffer: core::ptr::null(),
+            }; rfal_sys::NDEF_DEVICE_INFO_TYPE_COUNT as usize],
+        };
+        result(unsafe { rfal_sys::ndefGetRtdDeviceInfo(ty, &mut data) })?;
+        let mut info = Self {
+            manufacturer: &[],
+            model: &[],
+            unique_name: None,
+            uuid: None,
+            firmware_version: None,
+        };
+        for entry in data.devInfo.iter().filter(|e| e.length != 0) {
+            let value = unsafe { slice(entry.buffer, entry.length as usize) };
+            match entry.type_ as u32 {
+                rfal_sys::NDEF_DEVICE_INFO_MANUFACTURER_NAME => info.manufacturer = value,
+                rfal_sys::NDEF_DEVICE_INFO_MODEL_NAME => info.model = value,
+                rfal_sys::NDEF_DEVICE_INFO_DEVICE_UNIQUE_NAME => info.unique_name = Some(value),
+                rfal_sys::NDEF_DEVICE_INFO_UUID => info.uuid = Some(value),
+                rfal_sys::NDEF_DEVICE_INFO_FIRMWARE_VERSION => info.firmware_version = Some(value),
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+}
+
+impl Encode for DeviceInfo<'_> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        let fields = [
+            (
+                rfal_sys::NDEF_DEVICE_INFO_MANUFACTURER_NAME,
+                Some(self.manufacturer),
+            ),
+            (rfal_sys::NDEF_DEVICE_INFO_MODEL_NAME, Some(self.model)),
+            (
+                rfal_sys::NDEF_DEVICE_INFO_DEVICE_UNIQUE_NAME,
+                self.unique_name,
+            ),
+            (rfal_sys::NDEF_DEVICE_INFO_UUID, self.uuid),
+            (
+                rfal_sys::NDEF_DEVICE_INFO_FIRMWARE_VERSION,
+                self.firmware_version,
+            ),
+        ];
+        let mut entries = [ndefDeviceInfoEntry {
+            type_: 0,
+            length: 0,
+            buffer: core::ptr::null(),
+        }; rfal_sys::NDEF_DEVICE_INFO_TYPE_COUNT as usize];
+        let mut count = 0;
+        for (type_, value) in fields {
+            if let Some(value) = value {
+                if value.len() > u8::MAX as usize {
+                    return Err(Error::Param);
+                }
+                entries[count] = ndefDeviceInfoEntry {
+                    type_: type_ as u8,
+                    length: value.len() as u8,
+                    buffer: value.as_ptr(),
+                };
+                count += 1;
+            }
+        }
+        let mut ty = empty_type();
+        result(unsafe { rfal_sys::ndefRtdDeviceInfoInit(&mut ty, entries.as_ptr(), count as u8) })?;
+        encode_type(&ty, buf)
+    }
+}
+
+/// Android Application Record, an external type `android.com:pkg`
+/// holding the package name.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aar<'a> {
+    pub package: &'a [u8],
+}
+
+impl<'a> Aar<'a> {
+    pub(crate) fn from_type(ty: &ndefType) -> Result<Self> {
+        let mut package = ndefConstBuffer {
+            buffer: core::ptr::null(),
+            length: 0,
+        };
+        result(unsafe { rfal_sys::ndefGetRtdAar(ty, &mut package) })?;
+        Ok(Self {
+            package: unsafe { slice(package.buffer, package.length as usize) },
+        })
+    }
+}
+
+impl Encode for Aar<'_> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        let mut ty = empty_type();
+        result(unsafe { rfal_sys::ndefRtdAarInit(&mut ty, &const_buffer(self.package)) })?;
+        encode_type(&ty, buf)
+    }
+}
+
+/// NFC Forum external type record, typed `domain:type`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct External<'a> {
+    record_type: &'a [u8],
+    pub payload: &'a [u8],
+}
+
+impl<'a> External<'a> {
+    pub fn new(record_type: &'a [u8], payload: &'a [u8]) -> Result<Self> {
+        match record_type.iter().position(|&c| c == b':') {
+            Some(pos) if pos > 0 && pos + 1 < record_type.len() => Ok(Self {
+                record_type,
+                payload,
+            }),
+            _ => Err(Error::Param),
+        }
+    }
+    pub fn record_type(&self) -> &'a [u8] {
+        self.record_type
+    }
+    pub fn domain(&self) -> &'a [u8] {
+        self.split().0
+    }
+    pub fn name(&self) -> &'a [u8] {
+        self.split().1
+    }
+    fn split(&self) -> (&'a [u8], &'a [u8]) {
+        let pos = self
+            .record_type
+            .iter()
+            .position(|&c| c == b':')
+            .unwrap_or(self.record_type.len());
+        let (domain, name) = self.record_type.split_at(pos);
+        (domain, name.get(1..).unwrap_or_default())
+    }
+}
+
+impl Encode for External<'_> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        Record::new(Tnf::External, self.record_type, None, self.payload)?.encode(buf)
+    }
+}
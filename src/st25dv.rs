@@ -0,0 +1,333 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{result, Error, NfcV, Result};
+
+pub const ST25DV_PASSWORD_LEN: usize = rfal_sys::RFAL_ST25xV_PWD_LEN as usize;
+pub const ST25DV_MAILBOX_LEN: usize = 256;
+
+// IC manufacturer code in the UID, least significant byte first
+const UID_MFG_CODE_POS: usize = 6;
+// response flags byte, followed by the data
+const RES_FLAG_LEN: usize = 1;
+// flags, command, IC manufacturer code, UID and message length
+const WRITE_MSG_HEADER_LEN: usize = 12;
+
+/// Static configuration registers, written after presenting the configuration password.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum St25dvRegister {
+    Gpo = 0x00,
+    ItTime = 0x01,
+    EhMode = 0x02,
+    RfMngt = 0x03,
+    Rfa1ss = 0x04,
+    Enda1 = 0x05,
+    Rfa2ss = 0x06,
+    Enda2 = 0x07,
+    Rfa3ss = 0x08,
+    Enda3 = 0x09,
+    Rfa4ss = 0x0A,
+    I2css = 0x0B,
+    LockCcfile = 0x0C,
+    MbMode = 0x0D,
+    MbWdg = 0x0E,
+    LockCfg = 0x0F,
+}
+
+/// Dynamic registers, reset on power down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum St25dvDynRegister {
+    GpoCtrl = 0x00,
+    EhCtrl = 0x02,
+    RfMngt = 0x03,
+    I2cSso = 0x04,
+    ItSts = 0x05,
+    MbCtrl = 0x0D,
+    MbLen = 0x0E,
+}
+
+/// Passwords presented with [`St25dv::present_password`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum St25dvPassword {
+    Configuration = 0,
+    Area1 = 1,
+    Area2 = 2,
+    Area3 = 3,
+}
+
+/// MB_CTRL_Dyn, fast transfer mode mailbox control and status.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MailboxStatus {
+    pub enabled: bool,
+    /// A message written by the host is waiting for the RF side.
+    pub host_put_msg: bool,
+    /// A message written by the RF side is waiting for the host.
+    pub rf_put_msg: bool,
+    pub host_missed_msg: bool,
+    pub rf_missed_msg: bool,
+    pub host_current_msg: bool,
+    pub rf_current_msg: bool,
+}
+
+impl From<u8> for MailboxStatus {
+    fn from(value: u8) -> Self {
+        Self {
+            enabled: value & 0x01 != 0,
+            host_put_msg: value & 0x02 != 0,
+            rf_put_msg: value & 0x04 != 0,
+            host_missed_msg: value & 0x10 != 0,
+            rf_missed_msg: value & 0x20 != 0,
+            host_current_msg: value & 0x40 != 0,
+            rf_current_msg: value & 0x80 != 0,
+        }
+    }
+}
+
+/// EH_CTRL_Dyn, energy harvesting control and status.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EnergyHarvesting {
+    pub enabled: bool,
+    pub on: bool,
+    pub field_on: bool,
+    pub vcc_on: bool,
+}
+
+impl From<u8> for EnergyHarvesting {
+    fn from(value: u8) -> Self {
+        Self {
+            enabled: value & 0x01 != 0,
+            on: value & 0x02 != 0,
+            field_on: value & 0x04 != 0,
+            vcc_on: value & 0x08 != 0,
+        }
+    }
+}
+
+/// ST25DV dynamic tag commands on top of an NFC-V handle.
+pub struct St25dv {
+    nfcv: NfcV,
+}
+
+impl St25dv {
+    /// Fails with `Param` if the UID is not from an ST tag.
+    pub fn new(nfcv: NfcV) -> Result<Self> {
+        if nfcv.uid()[UID_MFG_CODE_POS] as u32 != rfal_sys::RFAL_NFCV_ST_IC_MFG_CODE {
+            return Err(Error::Param);
+        }
+        Ok(Self { nfcv })
+    }
+    /// Generic NFC-V access, memory blocks and addressing mode.
+    pub fn nfcv(&mut self) -> &mut NfcV {
+        &mut self.nfcv
+    }
+    pub fn release(self) -> NfcV {
+        self.nfcv
+    }
+    pub fn read_config(&mut self, register: St25dvRegister) -> Result<u8> {
+        let mut value = 0u8;
+        result(unsafe {
+            rfal_sys::rfalST25xVPollerReadConfiguration(
+                self.nfcv.flags(),
+                self.nfcv.uid_ptr(),
+                register as u8,
+                &mut value,
+            )
+        })?;
+        Ok(value)
+    }
+    /// Requires the configuration security session, see [`St25dv::present_password`].
+    pub fn write_config(&mut self, register: St25dvRegister, value: u8) -> Result<()> {
+        result(unsafe {
+            rfal_sys::rfalST25xVPollerWriteConfiguration(
+                self.nfcv.flags(),
+                self.nfcv.uid_ptr(),
+                register as u8,
+                value,
+            )
+        })
+    }
+    pub fn read_dyn_config(&mut self, register: St25dvDynRegister) -> Result<u8> {
+        let mut value = 0u8;
+        result(unsafe {
+            rfal_sys::rfalST25xVPollerReadDynamicConfiguration(
+                self.nfcv.flags(),
+                self.nfcv.uid_ptr(),
+                register as u8,
+                &mut value,
+            )
+        })?;
+        Ok(value)
+    }
+    pub fn write_dyn_config(&mut self, register: St25dvDynRegister, value: u8) -> Result<()> {
+        result(unsafe {
+            rfal_sys::rfalST25xVPollerWriteDynamicConfiguration(
+                self.nfcv.flags(),
+                self.nfcv.uid_ptr(),
+                register as u8,
+                value,
+            )
+        })
+    }
+    pub fn mailbox_status(&mut self) -> Result<MailboxStatus> {
+        self.read_dyn_config(St25dvDynRegister::MbCtrl)
+            .map(MailboxStatus::from)
+    }
+    /// Enables the mailbox, MB_MODE must allow it. Disabling it flushes its content.
+    pub fn set_mailbox_enabled(&mut self, enabled: bool) -> Result<()> {
+        self.write_dyn_config(St25dvDynRegister::MbCtrl, enabled as u8)
+    }
+    pub fn energy_harvesting(&mut self) -> Result<EnergyHarvesting> {
+        self.read_dyn_config(St25dvDynRegister::EhCtrl)
+            .map(EnergyHarvesting::from)
+    }
+    pub fn set_energy_harvesting(&mut self, enabled: bool) -> Result<()> {
+        self.write_dyn_config(St25dvDynRegister::EhCtrl, enabled as u8)
+    }
+    /// Whether the I2C security session is open on the host side.
+    pub fn i2c_security_session(&mut self) -> Result<bool> {
+        Ok(self.read_dyn_config(St25dvDynRegister::I2cSso)? & 0x01 != 0)
+    }
+    /// Opens the security session of `password`.
+    pub fn present_password(
+        &mut self,
+        password: St25dvPassword,
+        value: &[u8; ST25DV_PASSWORD_LEN],
+    ) -> Result<()> {
+        result(unsafe {
+            rfal_sys::rfalST25xVPollerPresentPassword(
+                self.nfcv.flags(),
+                self.nfcv.uid_ptr(),
+                password as u8,
+                value.as_ptr(),
+                value.len() as u8,
+            )
+        })
+    }
+    /// Changes `password`, its security session must be open.
+    pub fn write_password(
+        &mut self,
+        password: St25dvPassword,
+        value: &[u8; ST25DV_PASSWORD_LEN],
+    ) -> Result<()> {
+        result(unsafe {
+            rfal_sys::rfalST25xVPollerWritePassword(
+                self.nfcv.flags(),
+                self.nfcv.uid_ptr(),
+                password as u8,
+                value.as_ptr(),
+                value.len() as u8,
+            )
+        })
+    }
+    /// Fast transfer mode mailbox as a byte channel with the host.
+    pub fn mailbox(&mut self) -> Mailbox<'_> {
+        Mailbox {
+            dv: self,
+            msg: [0u8; ST25DV_MAILBOX_LEN],
+            pos: 0,
+            len: 0,
+        }
+    }
+    fn read_message_len(&mut self) -> Result<usize> {
+        let mut len = 0u8;
+        result(unsafe {
+            rfal_sys::rfalST25xVPollerReadMessageLength(
+                self.nfcv.flags(),
+                self.nfcv.uid_ptr(),
+                &mut len,
+            )
+        })?;
+        // MB_LEN is the message length minus 1
+        Ok(len as usize + 1)
+    }
+    fn read_message(&mut self, buf: &mut [u8; ST25DV_MAILBOX_LEN]) -> Result<usize> {
+        let mut rx = [0u8; RES_FLAG_LEN + ST25DV_MAILBOX_LEN];
+        let mut rcv_len = 0u16;
+        // pointer and number of bytes 0 return the whole message
+        result(unsafe {
+            rfal_sys::rfalST25xVPollerReadMessage(
+                self.nfcv.flags(),
+                self.nfcv.uid_ptr(),
+                0,
+                0,
+                rx.as_mut_ptr(),
+                rx.len() as u16,
+                &mut rcv_len,
+            )
+        })?;
+        let data = rx.get(RES_FLAG_LEN..rcv_len as usize).ok_or(Error::Proto)?;
+        buf[..data.len()].copy_from_slice(data);
+        Ok(data.len())
+    }
+    fn write_message(&mut self, data: &[u8]) -> Result<()> {
+        if data.is_empty() || data.len() > ST25DV_MAILBOX_LEN {
+            return Err(Error::Param);
+        }
+        let mut tx = [0u8; WRITE_MSG_HEADER_LEN + ST25DV_MAILBOX_LEN];
+        result(unsafe {
+            rfal_sys::rfalST25xVPollerWriteMessage(
+                self.nfcv.flags(),
+                self.nfcv.uid_ptr(),
+                (data.len() - 1) as u8,
+                data.as_ptr(),
+                tx.as_mut_ptr(),
+                tx.len() as u16,
+            )
+        })
+    }
+}
+
+/// Byte channel over the fast transfer mode mailbox, which holds one message
+/// of up to 256 bytes in one direction at a time.
+pub struct Mailbox<'a> {
+    dv: &'a mut St25dv,
+    // last message from the host, not yet consumed
+    msg: [u8; ST25DV_MAILBOX_LEN],
+    pos: usize,
+    len: usize,
+}
+
+impl Mailbox<'_> {
+    pub fn status(&mut self) -> Result<MailboxStatus> {
+        self.dv.mailbox_status()
+    }
+    /// Puts up to 256 bytes of `data` as one message for the host and returns
+    /// the number of bytes sent, 0 while the host has not read the previous one.
+    pub fn send(&mut self, data: &[u8]) -> Result<usize> {
+        let status = self.dv.mailbox_status()?;
+        if !status.enabled {
+            return Err(Error::WrongState);
+        }
+        if status.rf_put_msg || status.host_put_msg {
+            return Ok(0);
+        }
+        let len = data.len().min(ST25DV_MAILBOX_LEN);
+        self.dv.write_message(&data[..len])?;
+        Ok(len)
+    }
+    /// Reads bytes of the messages put by the host into `buf`, returns 0 when
+    /// no message is pending.
+    pub fn receive(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.pos == self.len {
+            let status = self.dv.mailbox_status()?;
+            if !status.enabled {
+                return Err(Error::WrongState);
+            }
+            if !status.host_put_msg {
+                return Ok(0);
+            }
+            // reading the last byte frees the mailbox for the next message
+            self.len = self.dv.read_message(&mut self.msg)?;
+            self.pos = 0;
+        }
+        let len = buf.len().min(self.len - self.pos);
+        buf[..len].copy_from_slice(&self.msg[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+    /// Length of the message currently in the mailbox, in either direction,
+    /// only meaningful while one is pending.
+    pub fn message_len(&mut self) -> Result<usize> {
+        self.dv.read_message_len()
+    }
+}
@@ -0,0 +1,129 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{result, Device, Error, Result};
+use rfal_sys::{rfalNfcDevType, rfalSt25tbListenDevice};
+
+pub const ST25TB_UID_LEN: usize = rfal_sys::RFAL_ST25TB_UID_LEN as usize;
+pub const ST25TB_BLOCK_LEN: usize = rfal_sys::RFAL_ST25TB_BLOCK_LEN as usize;
+
+// PCALL16 slots, not exported by rfal_st25tb.h
+const SLOTS: u8 = 16;
+
+/// ST25TB device found by the collision resolution.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct St25tbDevice(pub(crate) rfalSt25tbListenDevice);
+
+impl Default for St25tbDevice {
+    fn default() -> Self {
+        // allocate default values manually, thanks bingen to not deriving Default trait...
+        Self(rfalSt25tbListenDevice {
+            chipID: 0,
+            UID: [0; ST25TB_UID_LEN],
+            isDeselected: false,
+        })
+    }
+}
+
+impl St25tbDevice {
+    /// Chip ID of the current session, random after each power up.
+    pub fn chip_id(&self) -> u8 {
+        self.0.chipID
+    }
+    pub fn uid(&self) -> [u8; ST25TB_UID_LEN] {
+        self.0.UID
+    }
+    pub fn is_deselected(&self) -> bool {
+        self.0.isDeselected
+    }
+}
+
+/// ST25TB (ISO 14443-B') poller commands, they address the selected chip.
+pub struct St25tb {
+    chip_id: u8,
+}
+
+impl St25tb {
+    /// Sets the RF configuration for ST25TB polling, not needed after a discovery.
+    pub fn initialize() -> Result<()> {
+        result(unsafe { rfal_sys::rfalSt25tbPollerInitialize() })
+    }
+    /// Checks if a ST25TB is in the field and returns its Chip ID.
+    pub fn check_presence() -> Result<u8> {
+        let mut chip_id = 0u8;
+        result(unsafe { rfal_sys::rfalSt25tbPollerCheckPresence(&mut chip_id) })?;
+        Ok(chip_id)
+    }
+    /// Runs the full collision resolution, fills `devices` and returns the number found.
+    pub fn collision_resolution(devices: &mut [St25tbDevice]) -> Result<usize> {
+        let mut dev_cnt = 0u8;
+        result(unsafe {
+            rfal_sys::rfalSt25tbPollerCollisionResolution(
+                devices.len().min(u8::MAX as usize) as u8,
+                devices.as_mut_ptr() as *mut rfalSt25tbListenDevice,
+                &mut dev_cnt,
+            )
+        })?;
+        Ok(dev_cnt as usize)
+    }
+    /// INITIATE, moves the chips in the field to inventory and returns the Chip ID
+    /// of the one answering, `RfCollision` when several do.
+    pub fn initiate() -> Result<u8> {
+        let mut chip_id = 0u8;
+        result(unsafe { rfal_sys::rfalSt25tbPollerInitiate(&mut chip_id) })?;
+        Ok(chip_id)
+    }
+    /// PCALL16, chips answer in slot 0 if their random slot number is 0.
+    pub fn pcall() -> Result<u8> {
+        let mut chip_id = 0u8;
+        result(unsafe { rfal_sys::rfalSt25tbPollerPcall(&mut chip_id) })?;
+        Ok(chip_id)
+    }
+    /// SLOT_MARKER for `slot` 1 to 15, following a PCALL16.
+    pub fn slot_marker(slot: u8) -> Result<u8> {
+        if !(1..SLOTS).contains(&slot) {
+            return Err(Error::Param);
+        }
+        let mut chip_id = 0u8;
+        result(unsafe { rfal_sys::rfalSt25tbPollerSlotMarker(slot, &mut chip_id) })?;
+        Ok(chip_id)
+    }
+    /// Selects the chip with `chip_id`, deselecting any other.
+    pub fn select(chip_id: u8) -> Result<Self> {
+        result(unsafe { rfal_sys::rfalSt25tbPollerSelect(chip_id) })?;
+        Ok(Self { chip_id })
+    }
+    /// Selects the discovered ST25TB `device`.
+    pub fn new(device: &Device) -> Result<Self> {
+        if device.dev_type() != rfalNfcDevType::RFAL_NFC_LISTEN_TYPE_ST25TB {
+            return Err(Error::Param);
+        }
+        Self::select(device.st25tb().chip_id())
+    }
+    pub fn chip_id(&self) -> u8 {
+        self.chip_id
+    }
+    pub fn get_uid(&mut self) -> Result<[u8; ST25TB_UID_LEN]> {
+        let mut uid = [0u8; ST25TB_UID_LEN];
+        result(unsafe { rfal_sys::rfalSt25tbPollerGetUID(&mut uid) })?;
+        Ok(uid)
+    }
+    pub fn read_block(&mut self, address: u8) -> Result<[u8; ST25TB_BLOCK_LEN]> {
+        let mut block = [0u8; ST25TB_BLOCK_LEN];
+        result(unsafe { rfal_sys::rfalSt25tbPollerReadBlock(address, &mut block) })?;
+        Ok(block)
+    }
+    /// Writes `data` and reads it back, OTP and counter blocks may not match `data`.
+    pub fn write_block(&mut self, address: u8, data: [u8; ST25TB_BLOCK_LEN]) -> Result<()> {
+        result(unsafe { rfal_sys::rfalSt25tbPollerWriteBlock(address, &data) })
+    }
+    /// COMPLETION, the chip is deactivated until it leaves the field.
+    pub fn completion(self) -> Result<()> {
+        result(unsafe { rfal_sys::rfalSt25tbPollerCompletion() })
+    }
+    /// RESET_TO_INVENTORY, the chip goes back to inventory state.
+    pub fn reset_to_inventory(self) -> Result<()> {
+        result(unsafe { rfal_sys::rfalSt25tbPollerResetToInventory() })
+    }
+}
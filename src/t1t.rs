@@ -0,0 +1,182 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{result, Device, Error, Result};
+use rfal_sys::{rfalNfcDevType, rfalNfcaListenDeviceType, rfalT1TRidRes};
+
+pub const T1T_UID_LEN: usize = rfal_sys::RFAL_T1T_UID_LEN as usize;
+pub const T1T_HR_LEN: usize = rfal_sys::RFAL_T1T_HR_LENGTH as usize;
+pub const T1T_BLOCK_LEN: usize = 8;
+/// Blocks 0h to Eh of the static memory, as returned by RALL.
+pub const T1T_MEMORY_LEN: usize = 15 * T1T_BLOCK_LEN;
+
+// UID0 to UID6 in block 0
+const FULL_UID_LEN: usize = 7;
+// data area, blocks 1h to Ch, starting with the CC
+const DATA_START: usize = T1T_BLOCK_LEN;
+const DATA_END: usize = 0xD * T1T_BLOCK_LEN;
+// block Eh holds LOCK0, LOCK1 and OTP0 to OTP5
+const LOCK_START: usize = 0xE * T1T_BLOCK_LEN;
+const OTP_START: usize = LOCK_START + 2;
+
+// RFAL_TXRX_FLAGS_DEFAULT, all the default flags are 0
+const TXRX_FLAGS_DEFAULT: u32 = 0;
+// DRD of a write without erase in 1/fc, T1T 1.2 4.4.2
+const DRD_WRITE_NE: u32 = 36052;
+
+/// Header ROM and UID returned by RID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct T1tRid {
+    pub hr0: u8,
+    pub hr1: u8,
+    /// UID0 to UID3, echoed by every command.
+    pub uid: [u8; T1T_UID_LEN],
+}
+
+impl T1tRid {
+    /// HR0 upper nibble 1h, the tag supports NDEF.
+    pub fn is_ndef_capable(&self) -> bool {
+        self.hr0 & rfal_sys::RFAL_T1T_HR0_NDEF_MASK as u8
+            == rfal_sys::RFAL_T1T_HR0_NDEF_SUPPORT as u8
+    }
+    /// HR0 lower nibble 1h, only the 120 bytes static memory is present.
+    pub fn is_static_memory(&self) -> bool {
+        self.hr0 & 0x0F == 0x01
+    }
+}
+
+impl From<rfalT1TRidRes> for T1tRid {
+    fn from(value: rfalT1TRidRes) -> Self {
+        Self {
+            hr0: value.hr0,
+            hr1: value.hr1,
+            uid: value.uid,
+        }
+    }
+}
+
+/// Content of the static memory returned by RALL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct T1tDump {
+    pub hr: [u8; T1T_HR_LEN],
+    pub memory: [u8; T1T_MEMORY_LEN],
+}
+
+impl T1tDump {
+    /// UID0 to UID6.
+    pub fn uid(&self) -> [u8; FULL_UID_LEN] {
+        let mut uid = [0u8; FULL_UID_LEN];
+        uid.copy_from_slice(&self.memory[..FULL_UID_LEN]);
+        uid
+    }
+    /// Data area of blocks 1h to Ch, the first 4 bytes are the CC on NDEF tags.
+    pub fn data(&self) -> &[u8] {
+        &self.memory[DATA_START..DATA_END]
+    }
+    /// LOCK0 and LOCK1.
+    pub fn lock(&self) -> [u8; 2] {
+        [self.memory[LOCK_START], self.memory[LOCK_START + 1]]
+    }
+    /// Whether `block` (0h to Eh) is read-only, one lock bit per block.
+    pub fn is_block_locked(&self, block: u8) -> bool {
+        block <= 0xE && u16::from_le_bytes(self.lock()) & (1 << block) != 0
+    }
+    /// OTP0 to OTP5, each bit can only be set once.
+    pub fn otp(&self) -> [u8; 6] {
+        let mut otp = [0u8; 6];
+        otp.copy_from_slice(&self.memory[OTP_START..OTP_START + 6]);
+        otp
+    }
+}
+
+/// Raw commands to an activated Type 1 Tag (Topaz).
+pub struct T1t {
+    uid: [u8; T1T_UID_LEN],
+}
+
+impl T1t {
+    /// Sets the RF configuration for T1T, after the NFC-A technology detection.
+    pub fn initialize() -> Result<()> {
+        result(unsafe { rfal_sys::rfalT1TPollerInitialize() })
+    }
+    pub fn new(device: &Device) -> Result<Self> {
+        let nfca = device.nfca();
+        if device.dev_type() != rfalNfcDevType::RFAL_NFC_LISTEN_TYPE_NFCA
+            || nfca.type_ != rfalNfcaListenDeviceType::RFAL_NFCA_T1T
+        {
+            return Err(Error::Param);
+        }
+        Ok(Self::with_uid(nfca.ridRes.uid))
+    }
+    pub fn with_uid(uid: [u8; T1T_UID_LEN]) -> Self {
+        Self { uid }
+    }
+    pub fn uid(&self) -> [u8; T1T_UID_LEN] {
+        self.uid
+    }
+    /// RID, reads the header ROM and the UID.
+    pub fn rid(&mut self) -> Result<T1tRid> {
+        let mut res = rfalT1TRidRes {
+            hr0: 0,
+            hr1: 0,
+            uid: [0; T1T_UID_LEN],
+        };
+        result(unsafe { rfal_sys::rfalT1TPollerRid(&mut res) })?;
+        Ok(res.into())
+    }
+    /// RALL, reads the header ROM and the whole static memory.
+    pub fn rall(&mut self) -> Result<T1tDump> {
+        let mut rx = [0u8; T1T_HR_LEN + T1T_MEMORY_LEN];
+        let mut rcv_len = 0u16;
+        result(unsafe {
+            rfal_sys::rfalT1TPollerRall(
+                self.uid.as_ptr(),
+                rx.as_mut_ptr(),
+                rx.len() as u16,
+                &mut rcv_len,
+            )
+        })?;
+        if rcv_len as usize != rx.len() {
+            return Err(Error::Proto);
+        }
+        let mut dump = T1tDump {
+            hr: [rx[0], rx[1]],
+            memory: [0; T1T_MEMORY_LEN],
+        };
+        dump.memory.copy_from_slice(&rx[T1T_HR_LEN..]);
+        Ok(dump)
+    }
+    /// WRITE-E, erases then writes the byte at `address` (block << 3 | byte).
+    pub fn write_erase(&mut self, address: u8, data: u8) -> Result<()> {
+        result(unsafe { rfal_sys::rfalT1TPollerWrite(self.uid.as_ptr(), address, data) })
+    }
+    /// WRITE-NE, ORs `data` into the byte at `address` and returns the result,
+    /// used for lock and OTP bits.
+    pub fn write_no_erase(&mut self, address: u8, data: u8) -> Result<u8> {
+        let mut tx = [0u8; 3 + T1T_UID_LEN];
+        tx[..3].copy_from_slice(&[
+            rfal_sys::rfalT1Tcmds::RFAL_T1T_CMD_WRITE_NE as u8,
+            address,
+            data,
+        ]);
+        tx[3..].copy_from_slice(&self.uid);
+        // ADD and resulting DATA
+        let mut rx = [0u8; 2];
+        let mut rcv_len = 0u16;
+        result(unsafe {
+            rfal_sys::rfalTransceiveBlockingTxRx(
+                tx.as_mut_ptr(),
+                tx.len() as u16,
+                rx.as_mut_ptr(),
+                rx.len() as u16,
+                &mut rcv_len,
+                TXRX_FLAGS_DEFAULT,
+                DRD_WRITE_NE,
+            )
+        })?;
+        if rcv_len as usize != rx.len() || rx[0] != address {
+            return Err(Error::Proto);
+        }
+        Ok(rx[1])
+    }
+}
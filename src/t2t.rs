@@ -0,0 +1,258 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{result, Device, Error, Result};
+use rfal_sys::{rfalNfcDevType, rfalNfcaListenDeviceType};
+
+pub const T2T_PAGE_LEN: usize = rfal_sys::RFAL_T2T_BLOCK_LEN as usize;
+pub const T2T_READ_LEN: usize = rfal_sys::RFAL_T2T_READ_DATA_LEN as usize;
+/// Static lock bits of the CC page and of its block-locking bit, left out by [`T2t::lock`].
+pub const T2T_CC_LOCK_BITS: [u8; 2] = [0x09, 0x00];
+
+// static lock bytes are bytes 2 and 3 of this page
+const LOCK_PAGE: u8 = 2;
+// first page of the data area, where the TLV blocks start
+const DATA_PAGE: u8 = 4;
+const SECTOR_LEN: usize = 256 * T2T_PAGE_LEN;
+
+const TLV_NULL: u8 = 0x00;
+const TLV_LOCK_CONTROL: u8 = 0x01;
+const TLV_NDEF: u8 = 0x03;
+const TLV_TERMINATOR: u8 = 0xFE;
+
+/// Location of the dynamic lock bits, as described by a Lock Control TLV.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LockControl {
+    /// Address of the first dynamic lock byte, from the start of the tag memory.
+    pub byte_addr: usize,
+    pub size_bits: u16,
+    pub bytes_per_lock_bit: u16,
+}
+
+impl LockControl {
+    /// Decodes the 3 bytes value of a Lock Control TLV.
+    pub fn decode(value: &[u8]) -> Result<Self> {
+        let [position, size, page_control] = value else {
+            return Err(Error::Param);
+        };
+        let page_addr = (position >> 4) as usize;
+        let byte_offset = (position & 0x0F) as usize;
+        let bytes_per_page = 1usize << (page_control & 0x0F);
+        Ok(Self {
+            byte_addr: page_addr * bytes_per_page + byte_offset,
+            // a size of 0 stands for 256 bits
+            size_bits: if *size == 0 { 256 } else { *size as u16 },
+            bytes_per_lock_bit: 1 << (page_control >> 4),
+        })
+    }
+    /// Number of bytes holding the dynamic lock bits.
+    pub fn byte_len(&self) -> usize {
+        (self.size_bits as usize).div_ceil(8)
+    }
+    // unused bits of the last lock byte must be left untouched
+    fn mask(&self, index: usize) -> u8 {
+        let rem = self.size_bits % 8;
+        if index + 1 == self.byte_len() && rem != 0 {
+            (1u8 << rem) - 1
+        } else {
+            0xFF
+        }
+    }
+}
+
+/// Raw page access to an activated NFC-A Type 2 Tag.
+pub struct T2t<'d> {
+    device: &'d Device,
+    sector: u8,
+}
+
+impl<'d> T2t<'d> {
+    pub fn new(device: &'d Device) -> Result<Self> {
+        if device.dev_type() != rfalNfcDevType::RFAL_NFC_LISTEN_TYPE_NFCA
+            || device.nfca().type_ != rfalNfcaListenDeviceType::RFAL_NFCA_T2T
+        {
+            return Err(Error::Param);
+        }
+        Ok(Self { device, sector: 0 })
+    }
+    pub fn device(&self) -> &Device {
+        self.device
+    }
+    /// Reads 4 pages starting at `page`, rolling over to page 0 past the end of the sector.
+    pub fn read_pages(&mut self, page: u8) -> Result<[u8; T2T_READ_LEN]> {
+        let mut data = [0u8; T2T_READ_LEN];
+        let mut rcv_len = 0u16;
+        result(unsafe {
+            rfal_sys::rfalT2TPollerRead(page, data.as_mut_ptr(), data.len() as u16, &mut rcv_len)
+        })?;
+        if rcv_len as usize != data.len() {
+            return Err(Error::Proto);
+        }
+        Ok(data)
+    }
+    pub fn write_page(&mut self, page: u8, data: [u8; T2T_PAGE_LEN]) -> Result<()> {
+        result(unsafe { rfal_sys::rfalT2TPollerWrite(page, data.as_ptr()) })
+    }
+    /// Selects the memory sector of 256 pages used by the next page accesses.
+    pub fn sector_select(&mut self, sector: u8) -> Result<()> {
+        result(unsafe { rfal_sys::rfalT2TPollerSectorSelect(sector) })?;
+        self.sector = sector;
+        Ok(())
+    }
+    pub fn sector(&self) -> u8 {
+        self.sector
+    }
+    /// Reads the two static lock bytes.
+    pub fn static_lock(&mut self) -> Result<[u8; 2]> {
+        self.in_sector(0, |t2t| {
+            let data = t2t.read_pages(LOCK_PAGE)?;
+            Ok([data[2], data[3]])
+        })
+    }
+    /// Sets the given static lock bits, lock bits can never be cleared.
+    pub fn set_static_lock(&mut self, bits: [u8; 2]) -> Result<()> {
+        self.in_sector(0, |t2t| {
+            let data = t2t.read_pages(LOCK_PAGE)?;
+            // the tag ignores writes to the serial number and internal bytes
+            t2t.write_page(
+                LOCK_PAGE,
+                [data[0], data[1], data[2] | bits[0], data[3] | bits[1]],
+            )
+        })
+    }
+    /// Looks for a Lock Control TLV in front of the NDEF TLV.
+    pub fn lock_control(&mut self) -> Result<Option<LockControl>> {
+        self.in_sector(0, |t2t| {
+            let mut reader = ByteReader::default();
+            let mut addr = DATA_PAGE as usize * T2T_PAGE_LEN;
+            while addr < SECTOR_LEN {
+                let tlv = reader.read(t2t, addr)?;
+                addr += 1;
+                match tlv {
+                    TLV_NULL => continue,
+                    TLV_NDEF | TLV_TERMINATOR => break,
+                    _ => {}
+                }
+                let mut len = reader.read(t2t, addr)? as usize;
+                addr += 1;
+                if len == 0xFF {
+                    len = u16::from_be_bytes([reader.read(t2t, addr)?, reader.read(t2t, addr + 1)?])
+                        as usize;
+                    addr += 2;
+                }
+                if tlv == TLV_LOCK_CONTROL {
+                    let mut value = [0u8; 3];
+                    if len != value.len() {
+                        return Err(Error::Proto);
+                    }
+                    for (i, b) in value.iter_mut().enumerate() {
+                        *b = reader.read(t2t, addr + i)?;
+                    }
+                    return LockControl::decode(&value).map(Some);
+                }
+                addr += len;
+            }
+            Ok(None)
+        })
+    }
+    /// Reads the dynamic lock bytes described by `lock` into `buf`.
+    pub fn dynamic_lock(&mut self, lock: &LockControl, buf: &mut [u8]) -> Result<usize> {
+        let len = lock.byte_len();
+        if buf.len() < len {
+            return Err(Error::NoMem);
+        }
+        let sector = self.sector;
+        let mut reader = ByteReader::default();
+        let res = buf[..len].iter_mut().enumerate().try_for_each(|(i, b)| {
+            let addr = lock.byte_addr + i;
+            self.select((addr / SECTOR_LEN) as u8)?;
+            *b = reader.read(self, addr % SECTOR_LEN)? & lock.mask(i);
+            Ok(())
+        });
+        self.select(sector)?;
+        res.map(|_| len)
+    }
+    /// Sets the given dynamic lock bits, `bits` holds [`LockControl::byte_len`] bytes.
+    pub fn set_dynamic_lock(&mut self, lock: &LockControl, bits: &[u8]) -> Result<()> {
+        if bits.len() != lock.byte_len() {
+            return Err(Error::Param);
+        }
+        let sector = self.sector;
+        let res = self.write_dynamic_lock(lock, bits);
+        self.select(sector)?;
+        res
+    }
+    /// Sets all static and dynamic lock bits but [`T2T_CC_LOCK_BITS`], making the
+    /// data area read-only for good. The CC stays writable for
+    /// [`crate::Poller::set_read_only`], which locks it once updated on NDEF
+    /// tags, on other tags use [`T2t::set_static_lock`] once the CC is final.
+    pub fn lock(&mut self) -> Result<()> {
+        if let Some(lock) = self.lock_control()? {
+            // at most 256 lock bits
+            let bits = [0xFFu8; 32];
+            self.set_dynamic_lock(&lock, &bits[..lock.byte_len()])?;
+        }
+        self.set_static_lock([!T2T_CC_LOCK_BITS[0], !T2T_CC_LOCK_BITS[1]])
+    }
+    fn write_dynamic_lock(&mut self, lock: &LockControl, bits: &[u8]) -> Result<()> {
+        let mut i = 0;
+        while i < bits.len() {
+            let addr = lock.byte_addr + i;
+            self.select((addr / SECTOR_LEN) as u8)?;
+            let page = ((addr % SECTOR_LEN) / T2T_PAGE_LEN) as u8;
+            let mut data = [0u8; T2T_PAGE_LEN];
+            data.copy_from_slice(&self.read_pages(page)?[..T2T_PAGE_LEN]);
+            for byte in data.iter_mut().skip(addr % T2T_PAGE_LEN) {
+                if i == bits.len() {
+                    break;
+                }
+                *byte |= bits[i] & lock.mask(i);
+                i += 1;
+            }
+            self.write_page(page, data)?;
+        }
+        Ok(())
+    }
+    fn select(&mut self, sector: u8) -> Result<()> {
+        if self.sector != sector {
+            self.sector_select(sector)?;
+        }
+        Ok(())
+    }
+    fn in_sector<T>(&mut self, sector: u8, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let current = self.sector;
+        self.select(sector)?;
+        let res = f(self);
+        self.select(current)?;
+        res
+    }
+}
+
+// caches the last READ to avoid one exchange per byte
+#[derive(Default)]
+struct ByteReader {
+    // sector and first page of the cached pages
+    pages: Option<(u8, u8)>,
+    data: [u8; T2T_READ_LEN],
+}
+
+impl ByteReader {
+    fn read(&mut self, t2t: &mut T2t, addr: usize) -> Result<u8> {
+        if addr >= SECTOR_LEN {
+            return Err(Error::Param);
+        }
+        let page = (addr / T2T_PAGE_LEN) as u8;
+        let first = match self.pages {
+            Some((sector, first)) if sector == t2t.sector && page >= first && page - first < 4 => {
+                first
+            }
+            _ => {
+                self.data = t2t.read_pages(page)?;
+                self.pages.replace((t2t.sector, page));
+                page
+            }
+        };
+        let offset = (page - first) as usize * T2T_PAGE_LEN + addr % T2T_PAGE_LEN;
+        Ok(self.data[offset])
+    }
+}
@@ -0,0 +1,18 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// Milliseconds tick of the platform, wrapping around.
+pub(crate) fn now() -> u32 {
+    rfal_sys::ffi_get_ticks_ms()
+}
+
+/// Tick `ms` milliseconds after `now`.
+pub(crate) fn deadline(now: u32, ms: u32) -> u32 {
+    now.wrapping_add(ms)
+}
+
+/// Whether `deadline` is reached at `now`, robust to the wrap around as long
+/// as they are less than 24 days apart.
+pub(crate) fn expired(now: u32, deadline: u32) -> bool {
+    (now.wrapping_sub(deadline) as i32) >= 0
+}
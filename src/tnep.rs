@@ -0,0 +1,314 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{
+    record::{const_buffer, empty_type, encode_type, slice},
+    result, Encode, Error, MessageWriter, Poller, Record, Records, Result, Tnf,
+};
+use rfal_sys::{ndefConstBuffer, ndefType};
+
+/// TNEP communication mode advertised by a service.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommunicationMode {
+    SingleResponse,
+    Specific,
+    Rfu(u8),
+}
+
+impl From<u8> for CommunicationMode {
+    fn from(value: u8) -> Self {
+        match value as u32 {
+            rfal_sys::TNEP_COMMUNICATION_MODE_SINGLE_RESPONSE => CommunicationMode::SingleResponse,
+            rfal_sys::TNEP_COMMUNICATION_MODE_SPECIFIC => CommunicationMode::Specific,
+            _ => CommunicationMode::Rfu(value),
+        }
+    }
+}
+
+impl From<CommunicationMode> for u8 {
+    fn from(value: CommunicationMode) -> Self {
+        match value {
+            CommunicationMode::SingleResponse => {
+                rfal_sys::TNEP_COMMUNICATION_MODE_SINGLE_RESPONSE as u8
+            }
+            CommunicationMode::Specific => rfal_sys::TNEP_COMMUNICATION_MODE_SPECIFIC as u8,
+            CommunicationMode::Rfu(v) => v,
+        }
+    }
+}
+
+/// TNEP Service Parameter record, one per service offered by a tag
+/// in its initial NDEF message.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ServiceParameter<'a> {
+    pub version: u8,
+    pub service_uri: &'a [u8],
+    pub mode: CommunicationMode,
+    /// WT_INT, see [`ServiceParameter::twait_ms`].
+    pub wt_int: u8,
+    /// N_wait, number of waiting time extensions allowed.
+    pub max_extensions: u8,
+    pub max_message_size: u16,
+}
+
+impl<'a> ServiceParameter<'a> {
+    pub(crate) const RECORD_TYPE: &'static [u8] = b"Tp";
+
+    pub fn decode(payload: &'a [u8]) -> Result<Self> {
+        let record = Record::new(Tnf::WellKnown, Self::RECORD_TYPE, None, payload)?;
+        Self::from_type(&record.to_type()?)
+    }
+    pub(crate) fn from_type(ty: &ndefType) -> Result<Self> {
+        let mut version = 0u8;
+        let mut uri = ndefConstBuffer {
+            buffer: core::ptr::null(),
+            length: 0,
+        };
+        let mut mode = 0u8;
+        let mut wt_int = 0u8;
+        let mut max_extensions = 0u8;
+        let mut max_message_size = 0u16;
+        result(unsafe {
+            rfal_sys::ndefGetRtdTnepServiceParameter(
+                ty,
+                &mut version,
+                &mut uri,
+                &mut mode,
+                &mut wt_int,
+                &mut max_extensions,
+                &mut max_message_size,
+            )
+        })?;
+        Ok(Self {
+            version,
+            service_uri: unsafe { slice(uri.buffer, uri.length as usize) },
+            mode: mode.into(),
+            wt_int,
+            max_extensions,
+            max_message_size,
+        })
+    }
+    /// T_wait, minimum time between writing a request and reading the response,
+    /// rounded up to the next millisecond.
+    pub fn twait_ms(&self) -> u32 {
+        // T_wait = 2^(WT_INT/4 - 1) ms, 2^(k/4) in 32 bits fixed point for the
+        // fractional part of the exponent, instead of the powf of
+        // ndefRtdTnepServiceParameterComputeTwait() which is not built
+        const POW2_QUARTER: [u64; 4] = [4294967296, 5107605668, 6074001000, 7223245206];
+        let wt_int = self.wt_int & 0x3F;
+        let twait = POW2_QUARTER[(wt_int & 3) as usize] << (wt_int >> 2);
+        twait.div_ceil(1 << 33) as u32
+    }
+}
+
+impl Encode for ServiceParameter<'_> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        let mut ty = empty_type();
+        result(unsafe {
+            rfal_sys::ndefRtdTnepServiceParameterInit(
+                &mut ty,
+                self.version,
+                &const_buffer(self.service_uri),
+                self.mode.into(),
+                self.wt_int,
+                self.max_extensions,
+                self.max_message_size,
+            )
+        })?;
+        encode_type(&ty, buf)
+    }
+}
+
+/// TNEP Service Select record, written by the reader to select a service.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ServiceSelect<'a> {
+    pub service_uri: &'a [u8],
+}
+
+impl<'a> ServiceSelect<'a> {
+    pub fn decode(payload: &'a [u8]) -> Result<Self> {
+        let record = Record::new(Tnf::WellKnown, b"Ts", None, payload)?;
+        Self::from_type(&record.to_type()?)
+    }
+    pub(crate) fn from_type(ty: &ndefType) -> Result<Self> {
+        let mut uri = ndefConstBuffer {
+            buffer: core::ptr::null(),
+            length: 0,
+        };
+        result(unsafe { rfal_sys::ndefGetRtdTnepServiceSelect(ty, &mut uri) })?;
+        Ok(Self {
+            service_uri: unsafe { slice(uri.buffer, uri.length as usize) },
+        })
+    }
+}
+
+impl Encode for ServiceSelect<'_> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        let mut ty = empty_type();
+        result(unsafe {
+            rfal_sys::ndefRtdTnepServiceSelectInit(&mut ty, &const_buffer(self.service_uri))
+        })?;
+        encode_type(&ty, buf)
+    }
+}
+
+/// TNEP Status record, answered by the tag to a service selection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TnepStatus {
+    Success,
+    ProtocolError,
+    Rfu(u8),
+}
+
+impl TnepStatus {
+    pub(crate) const RECORD_TYPE: &'static [u8] = b"Te";
+
+    pub fn decode(payload: &[u8]) -> Result<Self> {
+        let record = Record::new(Tnf::WellKnown, Self::RECORD_TYPE, None, payload)?;
+        Self::from_type(&record.to_type()?)
+    }
+    pub(crate) fn from_type(ty: &ndefType) -> Result<Self> {
+        let mut status = 0u8;
+        result(unsafe { rfal_sys::ndefGetRtdTnepStatus(ty, &mut status) })?;
+        Ok(status.into())
+    }
+}
+
+impl From<u8> for TnepStatus {
+    fn from(value: u8) -> Self {
+        match value as u32 {
+            rfal_sys::TNEP_STATUS_TYPE_SUCCESS => TnepStatus::Success,
+            rfal_sys::TNEP_STATUS_TYPE_PROTOCOL_ERROR => TnepStatus::ProtocolError,
+            _ => TnepStatus::Rfu(value),
+        }
+    }
+}
+
+impl From<TnepStatus> for u8 {
+    fn from(value: TnepStatus) -> Self {
+        match value {
+            TnepStatus::Success => rfal_sys::TNEP_STATUS_TYPE_SUCCESS as u8,
+            TnepStatus::ProtocolError => rfal_sys::TNEP_STATUS_TYPE_PROTOCOL_ERROR as u8,
+            TnepStatus::Rfu(v) => v,
+        }
+    }
+}
+
+impl Encode for TnepStatus {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        let mut ty = empty_type();
+        result(unsafe { rfal_sys::ndefRtdTnepStatusInit(&mut ty, (*self).into()) })?;
+        encode_type(&ty, buf)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Timing {
+    twait_ms: u32,
+    max_extensions: u8,
+    max_message_size: u16,
+}
+
+impl From<&ServiceParameter<'_>> for Timing {
+    fn from(service: &ServiceParameter<'_>) -> Self {
+        Self {
+            twait_ms: service.twait_ms(),
+            max_extensions: service.max_extensions,
+            max_message_size: service.max_message_size,
+        }
+    }
+}
+
+/// TNEP reader (single response communication mode) on top of the NDEF poller,
+/// which must be initialized and have detected the NDEF of the tag.
+pub struct Tnep<'p> {
+    poller: &'p mut Poller,
+    service: Option<Timing>,
+}
+
+impl<'p> Tnep<'p> {
+    // SR header, "Ts", URI length and the longest URI
+    const SELECT_MAX_LEN: usize = 3 + 2 + 1 + u8::MAX as usize;
+
+    pub fn new(poller: &'p mut Poller) -> Self {
+        Self {
+            poller,
+            service: None,
+        }
+    }
+    /// Reads the initial NDEF message into `buf` and returns the services it advertises.
+    pub fn services<'b>(
+        &mut self,
+        buf: &'b mut [u8],
+    ) -> Result<impl Iterator<Item = ServiceParameter<'b>>> {
+        let len = self.poller.read_raw_message_into(buf, false)?;
+        let msg: &'b [u8] = &buf[..len];
+        Ok(Records::new(msg)
+            .filter_map(|record| record.ok())
+            .filter(|record| record.is_type(Tnf::WellKnown, ServiceParameter::RECORD_TYPE))
+            .filter_map(|record| ServiceParameter::decode(record.payload()).ok()))
+    }
+    /// Selects `service` and waits for the tag status, `buf` receives the response message.
+    pub fn select(&mut self, service: &ServiceParameter, buf: &mut [u8]) -> Result<()> {
+        if service.mode != CommunicationMode::SingleResponse {
+            return Err(Error::NotSupp);
+        }
+        self.service = None;
+        let mut request = [0u8; Self::SELECT_MAX_LEN];
+        let mut msg = MessageWriter::new(&mut request);
+        msg.push(&ServiceSelect {
+            service_uri: service.service_uri,
+        })?;
+        let request = msg.finish();
+        self.poller.write_raw_message(request)?;
+
+        let timing = Timing::from(service);
+        let response = self.wait_response(timing, request, buf)?;
+        let status = Records::new(response)
+            .filter_map(|record| record.ok())
+            .find(|record| record.is_type(Tnf::WellKnown, TnepStatus::RECORD_TYPE))
+            .ok_or(Error::Proto)
+            .and_then(|record| TnepStatus::from_type(&record.to_type()?))?;
+        match status {
+            TnepStatus::Success => {
+                self.service.replace(timing);
+                Ok(())
+            }
+            _ => Err(Error::Proto),
+        }
+    }
+    pub fn is_selected(&self) -> bool {
+        self.service.is_some()
+    }
+    /// Writes the `request` NDEF message to the selected service and reads its
+    /// response into `buf`, waiting T_wait up to N_wait extensions.
+    pub fn exchange<'b>(&mut self, request: &[u8], buf: &'b mut [u8]) -> Result<&'b [u8]> {
+        let timing = self.service.ok_or(Error::WrongState)?;
+        if request.len() > timing.max_message_size as usize {
+            return Err(Error::Param);
+        }
+        self.poller.write_raw_message(request)?;
+        self.wait_response(timing, request, buf)
+    }
+    fn wait_response<'b>(
+        &mut self,
+        timing: Timing,
+        request: &[u8],
+        buf: &'b mut [u8],
+    ) -> Result<&'b [u8]> {
+        let mut response_len = None;
+        for _ in 0..=timing.max_extensions {
+            rfal_sys::ffi_delay_ms(timing.twait_ms);
+            let len = self.poller.read_raw_message_into(buf, false)?;
+            // the tag has not replaced our request yet
+            if len != 0 && buf[..len] != *request {
+                response_len = Some(len);
+                break;
+            }
+        }
+        match response_len {
+            Some(len) => Ok(&buf[..len]),
+            None => Err(Error::Timeout),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::{result, Error, Result};
+use rfal_sys::{rfalWakeUpConfig, rfalWakeUpInfo, rfalWumPeriod};
+
+// highest DAC value of the ST25R95 tag detector
+const DAC_MAX: u16 = 0xFC;
+
+// reference returned by the last WakeUp::calibrate, above DAC_MAX until then
+static CALIBRATED: AtomicU8 = AtomicU8::new(u8::MAX);
+
+/// Reference the tag detector measurements are compared with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WakeUpReference {
+    /// Calibrated at initialization or by [`WakeUp::calibrate`], the delta is
+    /// checked against the reference of the latter.
+    Auto,
+    /// Explicit DAC value, 0 to 0xFC.
+    Dac(u8),
+}
+
+/// Wake-up mode configuration, [`Default`] matches the RFAL default one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WakeUpConfig {
+    /// How often the amplitude is measured, 100 to 800 ms.
+    pub period: rfalWumPeriod,
+    /// Amplitude delta around the reference that wakes up the reader.
+    pub delta: u8,
+    pub reference: WakeUpReference,
+}
+
+impl Default for WakeUpConfig {
+    fn default() -> Self {
+        Self {
+            period: rfalWumPeriod::RFAL_WUM_PERIOD_300MS,
+            delta: 8,
+            reference: WakeUpReference::Auto,
+        }
+    }
+}
+
+impl WakeUpConfig {
+    pub(crate) fn to_raw(self) -> Result<rfalWakeUpConfig> {
+        let reference = match self.reference {
+            WakeUpReference::Auto => {
+                // only known once calibrated from here, RFAL checks it otherwise
+                let dac = CALIBRATED.load(Ordering::Relaxed);
+                if dac as u16 <= DAC_MAX {
+                    check_window(dac, self.delta)?;
+                }
+                rfal_sys::RFAL_WUM_REFERENCE_AUTO as u16
+            }
+            WakeUpReference::Dac(dac) => {
+                check_window(dac, self.delta)?;
+                dac as u16
+            }
+        };
+        Ok(rfalWakeUpConfig {
+            period: self.period,
+            indAmp: rfal_sys::rfalWakeUpConfig__bindgen_ty_1 {
+                enabled: true,
+                delta: self.delta,
+                reference,
+            },
+        })
+    }
+}
+
+// the window of `delta` around the reference must stay within the DAC range,
+// RFAL computes its bounds in 8 bits
+fn check_window(reference: u8, delta: u8) -> Result<()> {
+    if delta > reference || reference as u16 + delta as u16 > DAC_MAX {
+        return Err(Error::Param);
+    }
+    Ok(())
+}
+
+/// Measurements of the last wake-up, the ST25R95 does not report any.
+pub struct WakeUpInfo(pub(crate) rfalWakeUpInfo);
+
+impl WakeUpInfo {
+    /// Chip dependent content.
+    pub fn raw(&self) -> &rfalWakeUpInfo {
+        &self.0
+    }
+}
+
+/// Wake-up mode, the reader idles until the antenna amplitude changes.
+pub struct WakeUp;
+
+impl WakeUp {
+    /// Starts the wake-up mode with `config`, or the RFAL default one.
+    pub fn start(config: Option<&WakeUpConfig>) -> Result<()> {
+        match config {
+            Some(config) => {
+                let config = config.to_raw()?;
+                result(unsafe { rfal_sys::rfalWakeUpModeStart(&config) })
+            }
+            None => result(unsafe { rfal_sys::rfalWakeUpModeStart(core::ptr::null()) }),
+        }
+    }
+    pub fn stop() -> Result<()> {
+        result(unsafe { rfal_sys::rfalWakeUpModeStop() })
+    }
+    /// Whether a tag was detected since the start, the worker must be running.
+    pub fn has_woken() -> bool {
+        unsafe { rfal_sys::rfalWakeUpModeHasWoke() }
+    }
+    pub fn is_enabled() -> bool {
+        unsafe { rfal_sys::rfalWakeUpModeIsEnabled() }
+    }
+    /// Information of the last measurement, `force` reads it even without a wake-up.
+    pub fn info(force: bool) -> Result<WakeUpInfo> {
+        let mut info = rfalWakeUpInfo { dummy: 0 };
+        result(unsafe { rfal_sys::rfalWakeUpModeGetInfo(force, &mut info) })?;
+        Ok(WakeUpInfo(info))
+    }
+    /// Calibrates the tag detector with the current antenna environment and
+    /// returns the DAC reference, used from now on by [`WakeUpReference::Auto`].
+    pub fn calibrate() -> Result<u8> {
+        let mut reference = 0u8;
+        result(unsafe { rfal_sys::rfalST25R95CalibrateTagDetector(&mut reference) })?;
+        CALIBRATED.store(reference, Ordering::Relaxed);
+        Ok(reference)
+    }
+}
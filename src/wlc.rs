@@ -0,0 +1,336 @@
+// SPDX-FileCopyrightText: 2024 Foundation Devices, Inc. <hello@foundation.xyz>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::{
+    record::{empty_type, encode_type},
+    result, Encode, Error, Record, Records, Result, Tnf,
+};
+use rfal_sys::{
+    ndefType, ndefTypeId, ndefTypeRtdWlcCapability, ndefTypeRtdWlcListenCtl,
+    ndefTypeRtdWlcPollInfo, ndefTypeRtdWlcStatusInfo,
+};
+
+/// WLC mode requested by the listener in its capability.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WlcMode {
+    Static,
+    Negotiated,
+    BatteryFull,
+    Rfu(u8),
+}
+
+impl From<u8> for WlcMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => WlcMode::Static,
+            1 => WlcMode::Negotiated,
+            2 => WlcMode::BatteryFull,
+            _ => WlcMode::Rfu(value),
+        }
+    }
+}
+
+impl From<WlcMode> for u8 {
+    fn from(value: WlcMode) -> Self {
+        match value {
+            WlcMode::Static => 0,
+            WlcMode::Negotiated => 1,
+            WlcMode::BatteryFull => 2,
+            WlcMode::Rfu(v) => v,
+        }
+    }
+}
+
+/// WLC Capability record (`WLCCAP`), exposed by a WLC listener in its NDEF message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WlcCapability {
+    pub protocol_version: u8,
+    pub mode_req: WlcMode,
+    pub wait_time_retry: u8,
+    pub nego_wait: bool,
+    pub rd_conf: bool,
+    pub cap_wt_int: u8,
+    pub ndef_rd_wt: u8,
+    pub ndef_write_to_int: u8,
+    pub ndef_write_wt_int: u8,
+}
+
+impl WlcCapability {
+    pub(crate) const RECORD_TYPE: &'static [u8] = b"WLCCAP";
+
+    pub fn decode(payload: &[u8]) -> Result<Self> {
+        let record = Record::new(Tnf::WellKnown, Self::RECORD_TYPE, None, payload)?;
+        Self::from_type(&record.to_type()?)
+    }
+    /// Looks for the capability of a WLC listener in a raw NDEF message.
+    pub fn find(msg: &[u8]) -> Option<Self> {
+        Records::new(msg)
+            .filter_map(|record| record.ok())
+            .find(|record| record.is_type(Tnf::WellKnown, Self::RECORD_TYPE))
+            .and_then(|record| Self::decode(record.payload()).ok())
+    }
+    pub(crate) fn from_type(ty: &ndefType) -> Result<Self> {
+        if ty.id != ndefTypeId::NDEF_TYPE_ID_RTD_WLCCAP {
+            return Err(Error::Param);
+        }
+        let cap = unsafe { ty.data.wlcCapability };
+        Ok(Self {
+            protocol_version: cap.wlcProtocolVersion,
+            mode_req: cap.wlcConfigModeReq.into(),
+            wait_time_retry: cap.wlcConfigWaitTimeRetry,
+            nego_wait: cap.wlcConfigNegoWait != 0,
+            rd_conf: cap.wlcConfigRdConf != 0,
+            cap_wt_int: cap.capWtInt,
+            ndef_rd_wt: cap.ndefRdWt,
+            ndef_write_to_int: cap.ndefWriteToInt,
+            ndef_write_wt_int: cap.ndefWriteWtInt,
+        })
+    }
+}
+
+impl Encode for WlcCapability {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        let cap = ndefTypeRtdWlcCapability {
+            wlcProtocolVersion: self.protocol_version,
+            wlcConfigModeReq: self.mode_req.into(),
+            wlcConfigWaitTimeRetry: self.wait_time_retry,
+            wlcConfigNegoWait: self.nego_wait as u8,
+            wlcConfigRdConf: self.rd_conf as u8,
+            capWtIntRfu: 0,
+            capWtInt: self.cap_wt_int,
+            ndefRdWt: self.ndef_rd_wt,
+            ndefWriteToInt: self.ndef_write_to_int,
+            ndefWriteWtInt: self.ndef_write_wt_int,
+        };
+        let mut ty = empty_type();
+        result(unsafe { rfal_sys::ndefRtdWlcCapabilityInit(&mut ty, &cap) })?;
+        encode_type(&ty, buf)
+    }
+}
+
+/// WLC Status and Information record (`WLCSTAI`), only the values
+/// flagged in the control byte are present.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WlcStatusInfo {
+    pub battery_level: Option<u8>,
+    pub receive_power: Option<u8>,
+    pub receive_voltage: Option<u8>,
+    pub receive_current: Option<u8>,
+    pub temperature_battery: Option<u8>,
+    pub temperature_wlcl: Option<u8>,
+    pub control_byte2: Option<u8>,
+}
+
+impl WlcStatusInfo {
+    pub fn decode(payload: &[u8]) -> Result<Self> {
+        let record = Record::new(Tnf::WellKnown, b"WLCSTAI", None, payload)?;
+        Self::from_type(&record.to_type()?)
+    }
+    pub(crate) fn from_type(ty: &ndefType) -> Result<Self> {
+        if ty.id != ndefTypeId::NDEF_TYPE_ID_RTD_WLCSTAI {
+            return Err(Error::Param);
+        }
+        let info = unsafe { ty.data.wlcStatusInfo };
+        let flag = |mask: u32, value: u8| (info.controlByte1 as u32 & mask != 0).then_some(value);
+        Ok(Self {
+            battery_level: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_BATTERY_LEVEL_MASK,
+                info.batteryLevel,
+            ),
+            receive_power: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_RECEIVE_POWER_MASK,
+                info.receivePower,
+            ),
+            receive_voltage: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_RECEIVE_VOLTAGE_MASK,
+                info.receiveVoltage,
+            ),
+            receive_current: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_RECEIVE_CURRENT_MASK,
+                info.receiveCurrent,
+            ),
+            temperature_battery: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_TEMPERATURE_BATTERY_MASK,
+                info.temperatureBattery,
+            ),
+            temperature_wlcl: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_TEMPERATURE_WLCL_MASK,
+                info.temperatureWlcl,
+            ),
+            control_byte2: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_CONTROL_BYTE_2_MASK,
+                info.controlByte2,
+            ),
+        })
+    }
+}
+
+impl Encode for WlcStatusInfo {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        let mut control = 0u32;
+        let mut flag = |mask: u32, value: Option<u8>| {
+            if value.is_some() {
+                control |= mask;
+            }
+            value.unwrap_or_default()
+        };
+        let mut info = ndefTypeRtdWlcStatusInfo {
+            controlByte1: 0,
+            batteryLevel: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_BATTERY_LEVEL_MASK,
+                self.battery_level,
+            ),
+            receivePower: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_RECEIVE_POWER_MASK,
+                self.receive_power,
+            ),
+            receiveVoltage: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_RECEIVE_VOLTAGE_MASK,
+                self.receive_voltage,
+            ),
+            receiveCurrent: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_RECEIVE_CURRENT_MASK,
+                self.receive_current,
+            ),
+            temperatureBattery: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_TEMPERATURE_BATTERY_MASK,
+                self.temperature_battery,
+            ),
+            temperatureWlcl: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_TEMPERATURE_WLCL_MASK,
+                self.temperature_wlcl,
+            ),
+            rfu: 0,
+            controlByte2: flag(
+                rfal_sys::NDEF_WLC_STATUSINFO_CONTROLBYTE1_CONTROL_BYTE_2_MASK,
+                self.control_byte2,
+            ),
+        };
+        info.controlByte1 = control as u8;
+        let mut ty = empty_type();
+        result(unsafe { rfal_sys::ndefRtdWlcStatusInfoInit(&mut ty, &info) })?;
+        encode_type(&ty, buf)
+    }
+}
+
+/// WLC Poll Information record (`WLCINF`), sent by the poller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WlcPollInfo {
+    /// Transmit power level.
+    pub p_tx: u8,
+    pub wlc_p_capability: u8,
+    pub power_class: u8,
+    pub total_power_steps: u8,
+    pub current_power_step: u8,
+    pub next_min_step_inc: u8,
+    pub next_min_step_dec: u8,
+}
+
+impl WlcPollInfo {
+    pub fn decode(payload: &[u8]) -> Result<Self> {
+        let record = Record::new(Tnf::WellKnown, b"WLCINF", None, payload)?;
+        Self::from_type(&record.to_type()?)
+    }
+    pub(crate) fn from_type(ty: &ndefType) -> Result<Self> {
+        if ty.id != ndefTypeId::NDEF_TYPE_ID_RTD_WLCINFO {
+            return Err(Error::Param);
+        }
+        let info = unsafe { ty.data.wlcPollInfo };
+        Ok(Self {
+            p_tx: info.pTx,
+            wlc_p_capability: info.wlcPCap,
+            power_class: info.powerClass,
+            total_power_steps: info.totPowerSteps,
+            current_power_step: info.curPowerStep,
+            next_min_step_inc: info.nextMinStepInc,
+            next_min_step_dec: info.nextMinStepDec,
+        })
+    }
+}
+
+impl Encode for WlcPollInfo {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        let info = ndefTypeRtdWlcPollInfo {
+            pTx: self.p_tx,
+            wlcPCap: self.wlc_p_capability,
+            powerClass: self.power_class,
+            totPowerSteps: self.total_power_steps,
+            curPowerStep: self.current_power_step,
+            nextMinStepInc: self.next_min_step_inc,
+            nextMinStepDec: self.next_min_step_dec,
+        };
+        let mut ty = empty_type();
+        result(unsafe { rfal_sys::ndefRtdWlcPollInfoInit(&mut ty, &info) })?;
+        encode_type(&ty, buf)
+    }
+}
+
+/// WLC Listen Control record (`WLCCTL`), sent by the listener to drive power transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WlcListenControl {
+    pub error: bool,
+    pub battery_status: u8,
+    pub counter: u8,
+    pub wpt_request: u8,
+    pub wpt_duration: u8,
+    pub info_request: bool,
+    pub power_adjust_request: u8,
+    pub battery_level: u8,
+    pub drv_flag: u8,
+    pub drv_int: u8,
+    pub hold_off_wt_int: u8,
+    /// Error info, only meaningful when `error` is set.
+    pub protocol_error: bool,
+    pub temperature_error: bool,
+}
+
+impl WlcListenControl {
+    pub fn decode(payload: &[u8]) -> Result<Self> {
+        let record = Record::new(Tnf::WellKnown, b"WLCCTL", None, payload)?;
+        Self::from_type(&record.to_type()?)
+    }
+    pub(crate) fn from_type(ty: &ndefType) -> Result<Self> {
+        if ty.id != ndefTypeId::NDEF_TYPE_ID_RTD_WLCCTL {
+            return Err(Error::Param);
+        }
+        let ctl = unsafe { ty.data.wlcListenCtl };
+        Ok(Self {
+            error: ctl.statusInfoErrorFlag != 0,
+            battery_status: ctl.statusInfoBatteryStatus,
+            counter: ctl.statusInfoCnt,
+            wpt_request: ctl.wptConfigWptReq,
+            wpt_duration: ctl.wptConfigWptDuration,
+            info_request: ctl.wptConfigInfoReq != 0,
+            power_adjust_request: ctl.powerAdjReq,
+            battery_level: ctl.batteryLevel,
+            drv_flag: ctl.drvInfoFlag,
+            drv_int: ctl.drvInfoInt,
+            hold_off_wt_int: ctl.holdOffWtInt,
+            protocol_error: ctl.errorInfoError != 0,
+            temperature_error: ctl.errorInfoTemperature != 0,
+        })
+    }
+}
+
+impl Encode for WlcListenControl {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize> {
+        let ctl = ndefTypeRtdWlcListenCtl {
+            statusInfoErrorFlag: self.error as u8,
+            statusInfoBatteryStatus: self.battery_status,
+            statusInfoCnt: self.counter,
+            wptConfigWptReq: self.wpt_request,
+            wptConfigWptDuration: self.wpt_duration,
+            wptConfigInfoReq: self.info_request as u8,
+            powerAdjReq: self.power_adjust_request,
+            batteryLevel: self.battery_level,
+            drvInfoFlag: self.drv_flag,
+            drvInfoInt: self.drv_int,
+            holdOffWtInt: self.hold_off_wt_int,
+            errorInfoError: self.protocol_error as u8,
+            errorInfoTemperature: self.temperature_error as u8,
+        };
+        let mut ty = empty_type();
+        result(unsafe { rfal_sys::ndefRtdWlcListenCtlInit(&mut ty, &ctl) })?;
+        encode_type(&ty, buf)
+    }
+}